@@ -1,6 +1,6 @@
 #![allow(unused)]
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 
 use steel::stdlib::PRELUDE;
 use steel::steel_vm::{engine::Engine, register_fn::RegisterFn};
@@ -312,6 +312,93 @@ fn binary_trees(c: &mut Criterion) {
     group.finish();
 }
 
+fn tokenize_prelude(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tokenize-prelude");
+    group.bench_function("collect", |b| {
+        b.iter(|| {
+            steel_parser::lexer::TokenStream::new(black_box(PRELUDE), true, None)
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("tokenize", |b| {
+        b.iter(|| steel_parser::lexer::tokenize(black_box(PRELUDE)))
+    });
+    group.finish();
+}
+
+// Lexer throughput in tokens per second, on inputs stressing different parts of it: string
+// literals are where building the contents of each token matters most, numbers go through
+// the most involved parsing.
+fn tokenize_throughput(c: &mut Criterion) {
+    let program = PRELUDE.repeat(4);
+    let strings: String = (0..2000)
+        .map(|i| format!("(display \"line {i} with \\\"escapes\\\" and\\n newlines\")\n"))
+        .collect();
+    let numbers: String = (0..2000)
+        .map(|i| format!("(+ {i} -{i}.5 {i}/7 #x{i:x} 1e{})\n", i % 300))
+        .collect();
+
+    let mut group = c.benchmark_group("tokenize-throughput");
+    for (name, input) in [
+        ("program", program),
+        ("strings", strings),
+        ("numbers", numbers),
+    ] {
+        let tokens = steel_parser::lexer::TokenStream::new(&input, true, None).count();
+        group.throughput(Throughput::Elements(tokens as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &input, |b, input| {
+            b.iter(|| steel_parser::lexer::tokenize(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+// Owning identifiers as `String`s allocates once per occurrence, interning them only once
+// per distinct name. The allocation counts are printed alongside the timings.
+fn tokenize_interning(c: &mut Criterion) {
+    use steel_parser::interner::Interner;
+    use steel_parser::lexer::{OwnedString, TokenStream};
+
+    let input: String = (0..2000)
+        .map(|i| {
+            format!(
+                "(define (f{} acc item) (g acc item (h acc item {i})))\n",
+                i % 50
+            )
+        })
+        .collect();
+
+    let identifiers = TokenStream::new(&input, true, None)
+        .filter(|token| token.ty.category() == steel_parser::tokens::TokenCategory::Identifier)
+        .count();
+    let mut interner = Interner::new();
+    TokenStream::new(&input, true, None)
+        .into_owned(&mut interner)
+        .for_each(drop);
+    println!(
+        "tokenize-interning: {identifiers} identifier allocations as strings, {} interned",
+        interner.len()
+    );
+
+    let mut group = c.benchmark_group("tokenize-interning");
+    group.bench_function("strings", |b| {
+        b.iter(|| {
+            TokenStream::new(black_box(&input), true, None)
+                .into_owned(OwnedString)
+                .count()
+        })
+    });
+    group.bench_function("interner", |b| {
+        b.iter(|| {
+            let mut interner = Interner::new();
+            TokenStream::new(black_box(&input), true, None)
+                .into_owned(&mut interner)
+                .count()
+        })
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     range,
@@ -327,6 +414,9 @@ criterion_group!(
     register_function,
     multiple_transducers,
     binary_trees,
+    tokenize_prelude,
+    tokenize_throughput,
+    tokenize_interning,
     // fib_28_contract,
     ackermann // trie_sort,
               // merge_sort,
@@ -2935,3 +2935,22 @@ impl<'a> ModuleBuilder<'a> {
         Ok(self)
     }
 }
+
+#[cfg(test)]
+mod bundled_module_tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_modules_parse() {
+        let sources = BUILT_INS
+            .iter()
+            .copied()
+            .chain([("kernel", crate::stdlib::KERNEL)]);
+
+        for (name, source) in sources {
+            if let Err(e) = Parser::parse(source) {
+                panic!("{name} doesn't parse: {e}");
+            }
+        }
+    }
+}
@@ -2102,6 +2102,109 @@ pub fn query_top_level_define<A: AsRef<str>>(
     None
 }
 
+/// The spans of the expressions in tail position inside of the functions in `exprs`, i.e.
+/// the ones whose value the function returns as is: the body of a `lambda`, and within an
+/// expression in tail position, the last expression of a `begin`, the body of a `let` and
+/// both branches of an `if`. A function call among them is a tail call. Top level
+/// expressions aren't inside of a function, so they are never in tail position.
+pub fn tail_positions(exprs: &[ExprKind]) -> Vec<Span> {
+    let mut visitor = TailPositions {
+        tail: false,
+        spans: Vec::new(),
+    };
+
+    for expr in exprs {
+        visitor.visit_in_position(expr, false);
+    }
+
+    visitor.spans
+}
+
+struct TailPositions {
+    // Whether the expression being visited is in tail position
+    tail: bool,
+    spans: Vec<Span>,
+}
+
+impl TailPositions {
+    fn visit_in_position<'a>(&mut self, expr: &'a ExprKind, tail: bool) {
+        let outer = self.tail;
+        self.tail = tail;
+
+        if tail
+            && !matches!(
+                expr,
+                ExprKind::Macro(_) | ExprKind::SyntaxRules(_) | ExprKind::Require(_)
+            )
+        {
+            self.spans.push(get_span(expr));
+        }
+
+        self.visit(expr);
+        self.tail = outer;
+    }
+}
+
+impl<'a> VisitorMutUnitRef<'a> for TailPositions {
+    fn visit_lambda_function(&mut self, lambda_function: &'a LambdaFunction) {
+        self.visit_in_position(&lambda_function.body, true);
+    }
+
+    fn visit_if(&mut self, f: &'a crate::parser::ast::If) {
+        let tail = self.tail;
+
+        self.visit_in_position(&f.test_expr, false);
+        self.visit_in_position(&f.then_expr, tail);
+        self.visit_in_position(&f.else_expr, tail);
+    }
+
+    fn visit_begin(&mut self, begin: &'a crate::parser::ast::Begin) {
+        let tail = self.tail;
+
+        if let Some((last, rest)) = begin.exprs.split_last() {
+            for expr in rest {
+                self.visit_in_position(expr, false);
+            }
+
+            self.visit_in_position(last, tail);
+        }
+    }
+
+    fn visit_let(&mut self, l: &'a Let) {
+        let tail = self.tail;
+
+        for (_, expr) in &l.bindings {
+            self.visit_in_position(expr, false);
+        }
+
+        self.visit_in_position(&l.body_expr, tail);
+    }
+
+    // The function and its arguments are all evaluated before the call
+    fn visit_list(&mut self, l: &'a List) {
+        for expr in &l.args {
+            self.visit_in_position(expr, false);
+        }
+    }
+
+    fn visit_define(&mut self, define: &'a Define) {
+        self.visit_in_position(&define.body, false);
+    }
+
+    fn visit_set(&mut self, s: &'a crate::parser::ast::Set) {
+        self.visit_in_position(&s.expr, false);
+    }
+
+    fn visit_return(&mut self, r: &'a crate::parser::ast::Return) {
+        self.visit_in_position(&r.expr, false);
+    }
+
+    // Quoted expressions are only data
+    fn visit_quote(&mut self, _quote: &'a Quote) {}
+
+    fn visit_macro(&mut self, _m: &'a crate::parser::ast::Macro) {}
+}
+
 struct FindCallSiteById<'a, F> {
     id: SyntaxObjectId,
     analysis: &'a Analysis,
@@ -4795,6 +4898,33 @@ mod analysis_pass_tests {
 
     use super::*;
 
+    #[test]
+    fn test_tail_positions() {
+        let script = r#"
+(define (f x)
+  (if (g x)
+      (h x)
+      (begin (k x) (f x))))
+(define l (lambda (x) (let ((y (g2 x))) (h2 y))))
+(f 10)
+"#;
+
+        let exprs = Parser::parse(script).unwrap();
+        let tails = tail_positions(&exprs)
+            .into_iter()
+            .map(|span| &script[span.start()..span.end()])
+            .collect::<Vec<_>>();
+
+        // Spans of lists only cover their elements, without the parentheses
+        for tail_call in ["h x", "f x", "h2 y"] {
+            assert!(tails.contains(&tail_call), "{tail_call} in {tails:?}");
+        }
+
+        for call in ["g x", "k x", "g2 x", "f 10"] {
+            assert!(!tails.contains(&call), "{call} in {tails:?}");
+        }
+    }
+
     #[test]
     fn test_unused_arguments() {
         let script = r#"
@@ -1,4 +1,4 @@
-use crate::rvals::{IntoSteelVal, SteelComplex, SteelString};
+use crate::rvals::{IntoSteelVal, SteelComplex};
 use crate::{parser::tokens::TokenType::*, rvals::FromSteelVal};
 
 use num::BigRational;
@@ -140,10 +140,6 @@ impl Sources {
     }
 }
 
-thread_local! {
-    static LAMBDA_SYMBOL: SteelString = "lambda".into();
-}
-
 fn real_literal_to_steelval(r: RealLiteral) -> Result<SteelVal, SteelErr> {
     match r {
         RealLiteral::Int(IntLiteral::Small(x)) => x.into_steelval(),
@@ -165,9 +161,21 @@ impl TryFrom<SyntaxObject> for SteelVal {
             CloseParen => {
                 Err(SteelErr::new(ErrorKind::UnexpectedToken, ")".to_string()).with_span(span))
             }
+            OpenCurly => {
+                Err(SteelErr::new(ErrorKind::UnexpectedToken, "{".to_string()).with_span(span))
+            }
+            CloseCurly => {
+                Err(SteelErr::new(ErrorKind::UnexpectedToken, "}".to_string()).with_span(span))
+            }
+            OpenVector => {
+                Err(SteelErr::new(ErrorKind::UnexpectedToken, "#(".to_string()).with_span(span))
+            }
+            OpenBytevector => {
+                Err(SteelErr::new(ErrorKind::UnexpectedToken, "#u8(".to_string()).with_span(span))
+            }
             CharacterLiteral(x) => Ok(CharV(x)),
-            BooleanLiteral(x) => Ok(BoolV(x)),
-            Identifier(x) => Ok(SymbolV(x.into())),
+            BooleanLiteral(x) | ShortBooleanLiteral(x) => Ok(BoolV(x)),
+            Identifier(x) => Ok(SteelVal::symbol(x.resolve())),
             Number(x) => match x {
                 NumberLiteral::Real(r) => real_literal_to_steelval(r),
                 NumberLiteral::Complex(re, im) => SteelComplex {
@@ -177,7 +185,7 @@ impl TryFrom<SyntaxObject> for SteelVal {
                 .into_steelval(),
             },
             StringLiteral(x) => Ok(StringV(x.into())),
-            Keyword(x) => Ok(SymbolV(x.into())),
+            Keyword(x) => Ok(SteelVal::symbol(x.resolve())),
             QuoteTick => {
                 Err(SteelErr::new(ErrorKind::UnexpectedToken, "'".to_string()).with_span(span))
             }
@@ -193,22 +201,37 @@ impl TryFrom<SyntaxObject> for SteelVal {
             Error => {
                 Err(SteelErr::new(ErrorKind::UnexpectedToken, "error".to_string()).with_span(span))
             }
-            Comment => Err(
+            Comment(_) => Err(
                 SteelErr::new(ErrorKind::UnexpectedToken, "comment".to_string()).with_span(span),
             ),
-            If => Ok(SymbolV("if".into())),
-            Define => Ok(SymbolV("define".into())),
-            Let => Ok(SymbolV("let".into())),
-            TestLet => Ok(SymbolV("%plain-let".into())),
-            Return => Ok(SymbolV("return!".into())),
-            Begin => Ok(SymbolV("begin".into())),
-            Lambda => Ok(SymbolV(LAMBDA_SYMBOL.with(|x| x.clone()))),
-            Quote => Ok(SymbolV("quote".into())),
-            DefineSyntax => Ok(SymbolV("define-syntax".into())),
-            SyntaxRules => Ok(SymbolV("syntax-rules".into())),
-            Ellipses => Ok(SymbolV("...".into())),
-            Set => Ok(SymbolV("set!".into())),
-            Require => Ok(SymbolV("require".into())),
+            DatumComment => {
+                Err(SteelErr::new(ErrorKind::UnexpectedToken, "#;".to_string()).with_span(span))
+            }
+            Comma => {
+                Err(SteelErr::new(ErrorKind::UnexpectedToken, ",".to_string()).with_span(span))
+            }
+            PartialString(_) => Err(SteelErr::new(
+                ErrorKind::UnexpectedToken,
+                "unterminated string".to_string(),
+            )
+            .with_span(span)),
+            Whitespace => Err(
+                SteelErr::new(ErrorKind::UnexpectedToken, "whitespace".to_string()).with_span(span),
+            ),
+            If => Ok(SteelVal::symbol("if")),
+            Define => Ok(SteelVal::symbol("define")),
+            Let => Ok(SteelVal::symbol("let")),
+            TestLet => Ok(SteelVal::symbol("%plain-let")),
+            Return => Ok(SteelVal::symbol("return!")),
+            Begin => Ok(SteelVal::symbol("begin")),
+            Lambda => Ok(SteelVal::symbol("lambda")),
+            Quote => Ok(SteelVal::symbol("quote")),
+            DefineSyntax => Ok(SteelVal::symbol("define-syntax")),
+            SyntaxRules => Ok(SteelVal::symbol("syntax-rules")),
+            Ellipses => Ok(SteelVal::symbol("...")),
+            Arrow => Ok(SteelVal::symbol("=>")),
+            Set => Ok(SteelVal::symbol("set!")),
+            Require => Ok(SteelVal::symbol("require")),
             QuasiQuoteSyntax => {
                 Err(SteelErr::new(ErrorKind::UnexpectedToken, "#`".to_string()).with_span(span))
             }
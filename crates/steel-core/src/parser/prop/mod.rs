@@ -49,6 +49,18 @@ proptest! {
     }
 }
 
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+    #[test]
+    fn test_display_round_trip(expr in round_trip_strategy()) {
+        let expr_string = expr.to_string();
+
+        let resulting_expr = Parser::parse(&expr_string).unwrap();
+
+        prop_assert_eq!(vec![expr], resulting_expr, "printed as {}", expr_string);
+    }
+}
+
 fn if_vec_strategy(
     inner: impl Strategy<Value = ExprKind> + Clone,
 ) -> impl Strategy<Value = ExprKind> {
@@ -118,6 +130,38 @@ fn exprkind_strategy() -> BoxedStrategy<ExprKind> {
     .boxed()
 }
 
+// Unlike `exprkind_strategy`, only generates expressions whose printed form is
+// expected to parse back to an equal expression.
+fn round_trip_strategy() -> BoxedStrategy<ExprKind> {
+    let leaf = round_trip_tokentype_strategy()
+        .prop_map(|ty| ExprKind::Atom(Atom::new(SyntaxObject::default(ty))));
+
+    leaf.prop_recursive(4, 64, 10, |inner| {
+        prop_oneof![
+            naive_list_vec_strategy(inner.clone()),
+            inner.prop_map(|x| {
+                ExprKind::Quote(Box::new(Quote::new(
+                    x,
+                    SyntaxObject::default(TokenType::Quote),
+                )))
+            })
+        ]
+    })
+    .boxed()
+}
+
+fn round_trip_tokentype_strategy() -> impl Strategy<Value = TokenType<InternedString>> {
+    use TokenType::*;
+    prop_oneof![
+        prop::char::range('A', 'Z').prop_map(CharacterLiteral),
+        any::<String>().prop_map(StringLiteral),
+        "x[a-z0-9?!*<>=-]{0,8}".prop_map(|x| Identifier(x.into())),
+        any::<isize>().prop_map(|x| IntLiteral::Small(x).into()),
+        any::<bool>().prop_map(BooleanLiteral),
+        (-1e15f64..1e15f64).prop_map(|x| RealLiteral::Float(x).into())
+    ]
+}
+
 prop_compose! {
     fn atom_strategy()(
         syntax_object in syntax_object_strategy()
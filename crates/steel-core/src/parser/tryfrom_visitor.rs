@@ -3,7 +3,11 @@ use steel_parser::parser::SyntaxObject;
 use crate::gc::Gc;
 use crate::values::lists::List;
 
-use crate::{parser::ast::ExprKind, rvals::Syntax};
+use crate::primitives::{bytevectors::bytevector, VectorOperations};
+use crate::{
+    parser::ast::{ExprKind, VectorKind},
+    rvals::Syntax,
+};
 
 use crate::rerrs::SteelErr;
 use crate::rvals::{Result, SteelVal};
@@ -125,6 +129,24 @@ impl ConsumingVisitor for TryFromExprKindForSteelVal {
     }
 
     fn visit_list(&mut self, l: super::ast::List) -> Self::Output {
+        match l.vector {
+            Some(VectorKind::Vector) => {
+                return VectorOperations::vec_construct_iter(
+                    l.args.into_iter().map(|x| self.visit(x)),
+                )
+            }
+            Some(VectorKind::Bytevector) => {
+                let bytes: Vec<_> = l
+                    .args
+                    .into_iter()
+                    .map(|x| self.visit(x))
+                    .collect::<Result<_>>()?;
+
+                return bytevector(&bytes);
+            }
+            None => {}
+        }
+
         let items: std::result::Result<List<_>, SteelErr> =
             l.args.into_iter().map(|x| self.visit(x)).collect();
 
@@ -926,4 +926,35 @@ mod try_from_tests {
         let res = String::try_from(&input);
         assert_eq!(res.unwrap(), expected);
     }
+
+    #[test]
+    fn round_trip_native_types() {
+        let value = 1.5f64.into_steelval().unwrap();
+        assert_eq!(f64::from_steelval(&value).unwrap(), 1.5);
+
+        let value = 42i64.into_steelval().unwrap();
+        assert_eq!(i64::from_steelval(&value).unwrap(), 42);
+
+        let value = true.into_steelval().unwrap();
+        assert!(bool::from_steelval(&value).unwrap());
+
+        let value = "foo".to_string().into_steelval().unwrap();
+        assert_eq!(String::from_steelval(&value).unwrap(), "foo");
+
+        let values = vec![SteelVal::IntV(1), SteelVal::BoolV(false)];
+        let value = values.clone().into_steelval().unwrap();
+        assert!(matches!(value, SteelVal::ListV(_)));
+        assert_eq!(Vec::<SteelVal>::from_steelval(&value).unwrap(), values);
+    }
+
+    #[test]
+    fn failed_conversion_is_a_conversion_error() {
+        let list = vec![1.0f64, 2.0].into_steelval().unwrap();
+
+        let err = f64::from_steelval(&list).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConversionError);
+
+        let err = i64::from_steelval(&SteelVal::BoolV(true)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ConversionError);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::rerrs::{ErrorKind, SteelErr};
 use crate::rvals::{Result, SteelVal};
 use crate::stop;
 
@@ -28,7 +29,10 @@ impl ControlOperations {
                     error_message.push_str(error_val.trim_matches('\"'));
                 }
 
-                stop!(Generic => error_message);
+                let message = args[0].to_string().trim_matches('\"').to_string();
+
+                Err(SteelErr::new(ErrorKind::Generic, error_message)
+                    .with_irritants(message, args[1..].to_vec()))
             } else {
                 stop!(ArityMismatch => "error takes at least one argument");
             }
@@ -243,22 +243,29 @@ pub fn divide_primitive(args: &[SteelVal]) -> Result<SteelVal> {
 }
 
 #[steel_derive::function(name = "exact?", constant = true)]
-pub fn exactp(value: &SteelVal) -> bool {
-    match value {
-        SteelVal::IntV(_)
-        | SteelVal::BigNum(_)
-        | SteelVal::Rational(_)
-        | SteelVal::BigRational(_) => true,
-        SteelVal::Complex(x) => exactp(&x.re) && exactp(&x.im),
-        _ => false,
+pub fn exactp(value: &SteelVal) -> Result<SteelVal> {
+    if !numberp(value) {
+        stop!(TypeMismatch => "exact? expects a number, found: {}", value)
     }
+    Ok(SteelVal::BoolV(is_exact(value)))
 }
 
 #[steel_derive::function(name = "inexact?", constant = true)]
-pub fn inexactp(value: &SteelVal) -> bool {
+pub fn inexactp(value: &SteelVal) -> Result<SteelVal> {
+    if !numberp(value) {
+        stop!(TypeMismatch => "inexact? expects a number, found: {}", value)
+    }
+    Ok(SteelVal::BoolV(!is_exact(value)))
+}
+
+/// A complex number is only exact if both of its parts are.
+fn is_exact(value: &SteelVal) -> bool {
     match value {
-        SteelVal::NumV(_) => true,
-        SteelVal::Complex(x) => inexactp(&x.re) || inexactp(&x.im),
+        SteelVal::IntV(_)
+        | SteelVal::BigNum(_)
+        | SteelVal::Rational(_)
+        | SteelVal::BigRational(_) => true,
+        SteelVal::Complex(x) => is_exact(&x.re) && is_exact(&x.im),
         _ => false,
     }
 }
@@ -608,7 +615,7 @@ fn sqrt(number: &SteelVal) -> Result<SteelVal> {
                 (n_sqrt / d_sqrt).into_steelval()?
             };
             if x.is_negative() {
-                let re = if exactp(&sqrt) {
+                let re = if is_exact(&sqrt) {
                     0.into_steelval()?
                 } else {
                     0.0.into_steelval()?
@@ -1029,7 +1036,7 @@ impl NumOperations {
             match &args[0] {
                 SteelVal::IntV(n) => Ok(SteelVal::BoolV(n & 1 == 0)),
                 SteelVal::BigNum(n) => Ok(SteelVal::BoolV(n.is_even())),
-                SteelVal::NumV(n) if n.fract() == 0.0 => (*n as i64).is_even().into_steelval(),
+                SteelVal::NumV(n) if n.fract() == 0.0 => Ok(SteelVal::BoolV(n % 2.0 == 0.0)),
                 _ => {
                     steelerr!(TypeMismatch => format!("even? requires an integer, found: {:?}", &args[0]))
                 }
@@ -1046,7 +1053,7 @@ impl NumOperations {
             match &args[0] {
                 SteelVal::IntV(n) => Ok(SteelVal::BoolV(n & 1 == 1)),
                 SteelVal::BigNum(n) => Ok(SteelVal::BoolV(n.is_odd())),
-                SteelVal::NumV(n) if n.fract() == 0.0 => (*n as i64).is_odd().into_steelval(),
+                SteelVal::NumV(n) if n.fract() == 0.0 => Ok(SteelVal::BoolV(n % 2.0 != 0.0)),
                 _ => {
                     steelerr!(TypeMismatch => format!("odd? requires an integer, found: {:?}", &args[0]))
                 }
@@ -1333,4 +1340,47 @@ mod num_op_tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn even_and_odd() {
+        let even = |x: SteelVal| NumOperations::even().func_or_else(|| ()).unwrap()(&[x]);
+        let odd = |x: SteelVal| NumOperations::odd().func_or_else(|| ()).unwrap()(&[x]);
+        let big = BigNum(Gc::new(BigInt::from_str("100000000000000000001").unwrap()));
+
+        assert_eq!(even(IntV(-4)).unwrap(), BoolV(true));
+        assert_eq!(odd(IntV(-3)).unwrap(), BoolV(true));
+        assert_eq!(even(big.clone()).unwrap(), BoolV(false));
+        assert_eq!(odd(big).unwrap(), BoolV(true));
+        assert_eq!(even(NumV(6.0)).unwrap(), BoolV(true));
+        assert_eq!(odd(NumV(-7.0)).unwrap(), BoolV(true));
+        // Too large to fit in an i64
+        assert_eq!(even(NumV(1e300)).unwrap(), BoolV(true));
+
+        assert!(even(NumV(2.5)).is_err());
+        assert!(odd(NumV(f64::INFINITY)).is_err());
+        assert!(even(Rational(Rational32::new(1, 2))).is_err());
+        assert!(odd(StringV("1".into())).is_err());
+    }
+
+    #[test]
+    fn exactness() {
+        let complex = |re, im| SteelComplex::new(re, im).into_steelval().unwrap();
+
+        for exact in [
+            IntV(1),
+            BigNum(Gc::new(BigInt::from(1) << 100)),
+            Rational(Rational32::new(1, 3)),
+            complex(IntV(1), IntV(2)),
+        ] {
+            assert_eq!(exactp(&exact).unwrap(), BoolV(true));
+            assert_eq!(inexactp(&exact).unwrap(), BoolV(false));
+        }
+        for inexact in [NumV(1.0), NumV(f64::NAN), complex(IntV(1), NumV(2.0))] {
+            assert_eq!(exactp(&inexact).unwrap(), BoolV(false));
+            assert_eq!(inexactp(&inexact).unwrap(), BoolV(true));
+        }
+
+        assert!(exactp(&StringV("1".into())).is_err());
+        assert!(inexactp(&BoolV(true)).is_err());
+    }
 }
@@ -18,6 +18,7 @@ pub fn port_module() -> BuiltInModule {
         .register_native_fn_definition(OPEN_INPUT_FILE_DEFINITION)
         .register_native_fn_definition(OPEN_OUTPUT_FILE_DEFINITION)
         .register_native_fn_definition(OPEN_OUTPUT_STRING_DEFINITION)
+        .register_native_fn_definition(OPEN_INPUT_STRING_DEFINITION)
         .register_native_fn_definition(WRITE_LINE_DEFINITION)
         .register_native_fn_definition(WRITE_STRING_DEFINITION)
         .register_native_fn_definition(WRITE_DEFINITION)
@@ -25,9 +26,13 @@ pub fn port_module() -> BuiltInModule {
         .register_native_fn_definition(FLUSH_OUTPUT_PORT_DEFINITION)
         .register_native_fn_definition(READ_PORT_TO_STRING_DEFINITION)
         .register_native_fn_definition(READ_LINE_TO_STRING_DEFINITION)
+        .register_native_fn_definition(READ_FROM_PORT_DEFINITION)
+        .register_native_fn_definition(IS_SEEKABLE_PORT_DEFINITION)
         .register_native_fn_definition(GET_OUTPUT_STRING_DEFINITION)
         .register_native_fn_definition(IS_INPUT_DEFINITION)
         .register_native_fn_definition(IS_OUTPUT_DEFINITION)
+        .register_native_fn_definition(IS_PORT_DEFINITION)
+        .register_native_fn_definition(CLOSE_PORT_DEFINITION)
         .register_native_fn_definition(DEFAULT_INPUT_PORT_DEFINITION)
         .register_native_fn_definition(DEFAULT_OUTPUT_PORT_DEFINITION);
     module
@@ -94,6 +99,19 @@ pub fn open_output_string() -> SteelVal {
     SteelVal::PortV(SteelPort::new_output_port())
 }
 
+/// Creates an input port that reads from the given string.
+///
+/// (open-input-string string?) -> input-port?
+///
+/// # Examples
+/// ```scheme
+/// > (open-input-string "(1 2) 3") ;; => #<port>
+/// ```
+#[function(name = "open-input-string")]
+pub fn open_input_string(string: &SteelString) -> SteelVal {
+    SteelVal::PortV(SteelPort::new_input_port_string(string.to_string()))
+}
+
 /// Takes a port and reads the entire content into a string
 ///
 /// (read-port-to-string port) -> string?
@@ -105,6 +123,21 @@ pub fn read_port_to_string(port: &SteelPort) -> Result<SteelVal> {
     Ok(SteelVal::StringV(result.into()))
 }
 
+/// Checks if a given value is a port, for input or output
+///
+/// (port? any/c) -> bool?
+///
+/// # Examples
+///
+/// ```scheme
+/// > (port? (open-input-string "foo")) ;; => #true
+/// > (port? "foo") ;; => #false
+/// ```
+#[function(name = "port?")]
+pub fn is_port(maybe_port: &SteelVal) -> bool {
+    matches!(maybe_port, SteelVal::PortV(_))
+}
+
 /// Checks if a given value is an input port
 ///
 /// (input-port? any/c) -> bool?
@@ -159,54 +192,66 @@ pub fn read_line_to_string(port: &SteelPort) -> Result<SteelVal> {
     }
 }
 
+/// Reads the next datum from a file or string port, leaving the port just past it.
+/// Returns the eof object once only whitespace and comments are left.
+///
+/// (read-from-port port) -> any/c
+///
+/// * port : input-port?
+///
+/// # Examples
+/// ```scheme
+/// > (define port (open-input-string "(1 2) foo"))
+/// > (read-from-port port) ;; => '(1 2)
+/// > (read-from-port port) ;; => 'foo
+/// > (read-from-port port) ;; => 'eof
+/// ```
+#[function(name = "read-from-port")]
+pub fn read_from_port(port: &SteelPort) -> Result<SteelVal> {
+    Ok(port
+        .read_datum()?
+        .unwrap_or_else(|| SteelVal::SymbolV(EOF_OBJECT.with(|x| x.clone()))))
+}
+
+#[function(name = "#%seekable-port?")]
+pub fn is_seekable_port(port: &SteelPort) -> bool {
+    port.is_seekable()
+}
+
 #[function(name = "write-line!")]
 pub fn write_line(port: &SteelPort, line: &SteelVal) -> Result<SteelVal> {
     let line = line.to_string();
-    let res = port.write_string_line(line.as_str());
+    port.write_string_line(line.as_str())?;
 
-    if res.is_ok() {
-        Ok(SteelVal::Void)
-    } else {
-        stop!(Generic => "unable to write string to file");
-    }
+    Ok(SteelVal::Void)
 }
 
 #[function(name = "raw-write")]
 pub fn write(port: &SteelPort, line: &SteelVal) -> Result<SteelVal> {
     let line = line.to_string();
-    let res = port.write_string(line.as_str());
+    port.write_string(line.as_str())?;
 
-    if res.is_ok() {
-        Ok(SteelVal::Void)
-    } else {
-        stop!(Generic => "unable to write string to port");
-    }
+    Ok(SteelVal::Void)
 }
 
 #[function(name = "raw-write-char")]
 pub fn write_char(port: &SteelPort, character: char) -> Result<SteelVal> {
-    let res = port.write_char(character);
+    port.write_char(character)?;
 
-    if res.is_ok() {
-        Ok(SteelVal::Void)
-    } else {
-        stop!(Generic => "unable to write string to port");
-    }
+    Ok(SteelVal::Void)
 }
 
 #[function(name = "raw-write-string")]
 pub fn write_string(port: &SteelPort, line: &SteelVal) -> Result<SteelVal> {
-    let res = if let SteelVal::StringV(s) = line {
-        port.write_string(s.as_str())
+    if let SteelVal::StringV(s) = line {
+        port.write_string(s.as_str())?;
+    } else if let SteelVal::CharV(c) = line {
+        port.write_char(*c)?;
     } else {
-        port.write_string(line.to_string().as_str())
-    };
-
-    if res.is_ok() {
-        Ok(SteelVal::Void)
-    } else {
-        stop!(Generic => "unable to write string to port");
+        port.write_string(line.to_string().as_str())?;
     }
+
+    Ok(SteelVal::Void)
 }
 
 #[function(name = "get-output-string")]
@@ -235,3 +280,22 @@ pub fn default_output_port() -> SteelVal {
 pub fn close_output_port(port: &SteelPort) -> Result<SteelVal> {
     port.close_output_port().map(|_| SteelVal::Void)
 }
+
+/// Closes the port, after which reading from or writing to it is an error. Closing a port
+/// that is already closed does nothing.
+///
+/// (close-port port) -> void?
+///
+/// * port : port?
+///
+/// # Examples
+/// ```scheme
+/// > (define port (open-input-string "foo"))
+/// > (close-port port)
+/// > (read-from-port port) ;; error: cannot read a datum from a closed port
+/// ```
+#[function(name = "close-port")]
+pub fn close_port(port: &SteelPort) -> SteelVal {
+    port.close();
+    SteelVal::Void
+}
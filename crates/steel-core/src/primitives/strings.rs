@@ -1,11 +1,12 @@
 use crate::values::lists::List;
 
-use crate::rvals::{RestArgsIter, Result, SteelString, SteelVal};
+use crate::rvals::{IntoSteelVal, RestArgsIter, Result, SteelString, SteelVal};
 use crate::steel_vm::builtin::BuiltInModule;
 use crate::steel_vm::register_fn::RegisterFn;
 use crate::stop;
 
 use steel_derive::{function, native};
+use steel_parser::tokens::RealLiteral;
 
 fn char_upcase(c: char) -> char {
     c.to_ascii_uppercase()
@@ -16,6 +17,14 @@ fn char_upcase(c: char) -> char {
 /// Strings in Steel are immutable, fixed length arrays of characters. They are heap allocated, and
 /// are implemented under the hood as referenced counted Rust `Strings`. Rust `Strings` are stored
 /// as UTF-8 encoded bytes.
+///
+/// Since strings are shared rather than copied, they can't be changed in place. Programs that need
+/// to replace characters one at a time can use a mutable string instead, created with
+/// `make-mutable-string` and updated with `string-set!`. Mutable strings store a `Vec<char>`
+/// rather than UTF-8 bytes: replacing a character in a UTF-8 buffer would mean shifting the rest
+/// of the bytes whenever the encodings of the old and new character differ in length, whereas a
+/// `char` slot can be overwritten directly. Use `mutable-string->string` to get a regular string
+/// back out.
 #[steel_derive::define_module(name = "steel/strings")]
 pub fn string_module() -> BuiltInModule {
     let mut module = BuiltInModule::new("steel/strings");
@@ -42,6 +51,11 @@ pub fn string_module() -> BuiltInModule {
         .register_native_fn_definition(STRING_REF_DEFINITION)
         .register_native_fn_definition(SUBSTRING_DEFINITION)
         .register_native_fn_definition(MAKE_STRING_DEFINITION)
+        .register_native_fn_definition(MAKE_MUTABLE_STRING_DEFINITION)
+        .register_fn("string-set!", MutableString::string_set)
+        .register_fn("mutable-string-ref", MutableString::string_ref)
+        .register_fn("mutable-string-length", MutableString::string_length)
+        .register_fn("mutable-string->string", MutableString::to_immutable_string)
         .register_native_fn_definition(STRING_EQUALS_DEFINITION)
         .register_native_fn_definition(STRING_CI_EQUALS_DEFINITION)
         .register_native_fn_definition(STRING_LESS_THAN_DEFINITION)
@@ -84,13 +98,26 @@ fn number_to_string_impl(value: &SteelVal, radix: Option<u32>) -> Result<SteelVa
                 Ok(SteelVal::StringV(v.to_string().into()))
             }
         }
-        SteelVal::NumV(n) => Ok(SteelVal::StringV(n.to_string().into())),
+        // Written like the literal, so that e.g. infinity is `+inf.0` rather than `inf`
+        SteelVal::NumV(n) => Ok(SteelVal::StringV(RealLiteral::Float(*n).to_string().into())),
         SteelVal::BigNum(n) => Ok(SteelVal::StringV(n.to_string().into())),
         _ => stop!(TypeMismatch => "number->string expects a number type, found: {}", value),
     }
 }
 
-/// Converts the given number to a string
+/// Converts the given number to a string, optionally in the given radix.
+///
+/// With a group size as well, the digits of an integer are split into groups of that many
+/// digits counting from the right, separated by `_`, for reading large numbers more easily.
+///
+/// (number->string n [radix] [group-size]) -> string?
+///
+/// # Examples
+/// ```scheme
+/// > (number->string 255 16) ;; => "ff"
+/// > (number->string 1000000 10 3) ;; => "1_000_000"
+/// > (number->string 65535 16 2) ;; => "ff_ff"
+/// ```
 #[function(name = "number->string", constant = true)]
 pub fn number_to_string(value: &SteelVal, mut rest: RestArgsIter<'_, isize>) -> Result<SteelVal> {
     let radix = rest.next();
@@ -107,7 +134,49 @@ pub fn number_to_string(value: &SteelVal, mut rest: RestArgsIter<'_, isize>) ->
         None
     };
 
-    number_to_string_impl(value, radix)
+    let group_size = if let Some(group_size) = rest.next() {
+        let group_size = group_size?;
+
+        if group_size < 1 {
+            stop!(ContractViolation => "number->string group size must be positive, found: {}", group_size);
+        }
+
+        if !matches!(value, SteelVal::IntV(_) | SteelVal::BigNum(_)) {
+            stop!(ContractViolation => "number->string can only group the digits of integers, found: {}", value);
+        }
+
+        Some(group_size as usize)
+    } else {
+        None
+    };
+
+    let string = number_to_string_impl(value, radix)?;
+
+    match (string, group_size) {
+        (SteelVal::StringV(s), Some(group_size)) => {
+            Ok(SteelVal::StringV(group_digits(&s, group_size).into()))
+        }
+        (string, _) => Ok(string),
+    }
+}
+
+// Separates the digits of an integer into groups of `size` with `_`, counting from the
+// right so that only the leftmost group can be short.
+fn group_digits(number: &str, size: usize) -> String {
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", number),
+    };
+
+    let mut grouped = String::from(sign);
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % size == 0 {
+            grouped.push('_');
+        }
+        grouped.push(digit);
+    }
+
+    grouped
 }
 
 fn string_to_number_impl(value: &str, radix: Option<u32>) -> Result<SteelVal> {
@@ -241,6 +310,18 @@ pub fn substring(value: &SteelString, i: usize, j: usize) -> Result<SteelVal> {
     Ok(SteelVal::StringV(value[i..j].into()))
 }
 
+/// Creates a string of length `k`, with every character set to `c`, or to the null character
+/// if `c` isn't given.
+///
+/// (make-string k [c]) -> string?
+///
+/// * k : int?
+/// * c : char?
+///
+/// # Examples
+/// ```scheme
+/// > (make-string 3 #\a) ;; => "aaa"
+/// ```
 #[function(name = "make-string")]
 pub fn make_string(k: usize, mut c: RestArgsIter<'_, char>) -> Result<SteelVal> {
     // If the char is there, we want to take it
@@ -255,6 +336,72 @@ pub fn make_string(k: usize, mut c: RestArgsIter<'_, char>) -> Result<SteelVal>
     Ok((0..k).into_iter().map(|_| c).collect::<String>().into())
 }
 
+/// A fixed length string whose characters can be replaced in place.
+///
+/// The characters are kept as a `Vec<char>`, so indices count characters and `string-set!`
+/// overwrites a single slot without touching the rest of the string.
+#[derive(Clone, Debug)]
+pub struct MutableString {
+    chars: Vec<char>,
+}
+
+impl crate::rvals::Custom for MutableString {}
+
+impl MutableString {
+    fn check_index(&self, name: &str, index: usize) -> Result<()> {
+        if index >= self.chars.len() {
+            stop!(Generic => "{}: index out of bounds - attempted to index at offset: {} with length {}", name, index, self.chars.len());
+        }
+
+        Ok(())
+    }
+
+    fn string_set(&mut self, index: usize, c: char) -> Result<()> {
+        self.check_index("string-set!", index)?;
+        self.chars[index] = c;
+        Ok(())
+    }
+
+    fn string_ref(&self, index: usize) -> Result<char> {
+        self.check_index("mutable-string-ref", index)?;
+        Ok(self.chars[index])
+    }
+
+    fn string_length(&self) -> usize {
+        self.chars.len()
+    }
+
+    fn to_immutable_string(&self) -> SteelVal {
+        SteelVal::StringV(self.chars.iter().collect::<String>().into())
+    }
+}
+
+/// Creates a mutable string of length `k`, with every character set to `c`, or to the null
+/// character if `c` isn't given. Its characters can be replaced with `string-set!`.
+///
+/// (make-mutable-string k [c]) -> mutable-string?
+///
+/// * k : int?
+/// * c : char?
+///
+/// # Examples
+/// ```scheme
+/// > (define s (make-mutable-string 3 #\a))
+/// > (string-set! s 1 #\b)
+/// > (mutable-string->string s) ;; => "aba"
+/// ```
+#[function(name = "make-mutable-string")]
+pub fn make_mutable_string(k: usize, mut c: RestArgsIter<'_, char>) -> Result<SteelVal> {
+    let char = c.next();
+
+    if let Some(next) = c.next() {
+        stop!(ArityMismatch => format!("make-mutable-string expected 1 or 2 arguments, got an additional argument {}", next?))
+    }
+
+    let c = char.unwrap_or(Ok('\0'))?;
+    MutableString { chars: vec![c; k] }.into_steelval()
+}
+
 #[function(name = "string-replace")]
 pub fn replace(value: &SteelString, from: &SteelString, to: &SteelString) -> Result<SteelVal> {
     Ok(SteelVal::StringV(
@@ -302,7 +449,7 @@ pub fn to_string(args: &[SteelVal]) -> Result<SteelVal> {
 /// ```
 #[function(name = "string->symbol", constant = true)]
 pub fn string_to_symbol(value: SteelString) -> SteelVal {
-    SteelVal::SymbolV(value)
+    SteelVal::symbol(value.as_str())
 }
 
 /// Converts an integer into a string.
@@ -569,6 +716,7 @@ pub fn string_append(mut rest: RestArgsIter<'_, &SteelString>) -> Result<SteelVa
 mod string_operation_tests {
     use super::*;
     use crate::rerrs::ErrorKind;
+    use crate::rvals::FromSteelVal;
 
     // TODO combine these 3 macros into one
     macro_rules! apply_tests_arity_too_many {
@@ -646,6 +794,144 @@ mod string_operation_tests {
         ("split-whitespace", split_whitespace_arity_takes_string, steel_split_whitespace)
     }
 
+    #[test]
+    fn number_to_string_groups_decimal_digits() {
+        let grouped = |args: &[SteelVal]| steel_number_to_string(args).unwrap();
+
+        assert_eq!(
+            grouped(&[
+                SteelVal::IntV(1000000),
+                SteelVal::IntV(10),
+                SteelVal::IntV(3)
+            ]),
+            SteelVal::StringV("1_000_000".into())
+        );
+        assert_eq!(
+            grouped(&[
+                SteelVal::IntV(-12345),
+                SteelVal::IntV(10),
+                SteelVal::IntV(3)
+            ]),
+            SteelVal::StringV("-12_345".into())
+        );
+        assert_eq!(
+            grouped(&[SteelVal::IntV(999), SteelVal::IntV(10), SteelVal::IntV(3)]),
+            SteelVal::StringV("999".into())
+        );
+    }
+
+    #[test]
+    fn number_to_string_groups_hex_digits() {
+        let grouped = |args: &[SteelVal]| steel_number_to_string(args).unwrap();
+
+        assert_eq!(
+            grouped(&[
+                SteelVal::IntV(0xffff),
+                SteelVal::IntV(16),
+                SteelVal::IntV(2)
+            ]),
+            SteelVal::StringV("ff_ff".into())
+        );
+        assert_eq!(
+            grouped(&[
+                SteelVal::IntV(0xabcde),
+                SteelVal::IntV(16),
+                SteelVal::IntV(4)
+            ]),
+            SteelVal::StringV("a_bcde".into())
+        );
+    }
+
+    #[test]
+    fn special_floats_are_written_like_r7rs_literals() {
+        use steel_parser::lexer::Lexer;
+        use steel_parser::tokens::{NumberLiteral, TokenType};
+
+        for (value, expected) in [
+            (f64::INFINITY, "+inf.0"),
+            (f64::NEG_INFINITY, "-inf.0"),
+            (f64::NAN, "+nan.0"),
+        ] {
+            let displayed = SteelVal::NumV(value).to_string();
+            assert_eq!(displayed, expected);
+            assert_eq!(
+                steel_number_to_string(&[SteelVal::NumV(value)]).unwrap(),
+                SteelVal::StringV(expected.into())
+            );
+
+            let Some(Ok(TokenType::Number(NumberLiteral::Real(RealLiteral::Float(read))))) =
+                Lexer::new(&displayed).next()
+            else {
+                panic!("{displayed} didn't read back as a float");
+            };
+            assert!(read == value || read.is_nan() && value.is_nan(), "{read}");
+        }
+    }
+
+    #[test]
+    fn number_to_string_only_groups_integers() {
+        let res =
+            steel_number_to_string(&[SteelVal::NumV(1.5), SteelVal::IntV(10), SteelVal::IntV(3)]);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::ContractViolation);
+
+        let res =
+            steel_number_to_string(&[SteelVal::IntV(1000), SteelVal::IntV(10), SteelVal::IntV(0)]);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::ContractViolation);
+    }
+
+    #[test]
+    fn make_string_with_fill() {
+        let args = vec![SteelVal::IntV(3), SteelVal::CharV('λ')];
+        let res = steel_make_string(&args);
+        let expected = SteelVal::StringV("λλλ".into());
+        assert_eq!(res.unwrap(), expected);
+    }
+
+    #[test]
+    fn make_string_without_fill() {
+        let args = vec![SteelVal::IntV(2)];
+        let res = steel_make_string(&args);
+        let expected = SteelVal::StringV("\0\0".into());
+        assert_eq!(res.unwrap(), expected);
+    }
+
+    #[test]
+    fn make_string_arity_too_many() {
+        let args = vec![
+            SteelVal::IntV(2),
+            SteelVal::CharV('a'),
+            SteelVal::CharV('b'),
+        ];
+        let res = steel_make_string(&args);
+        let expected = ErrorKind::ArityMismatch;
+        assert_eq!(res.unwrap_err().kind(), expected);
+    }
+
+    #[test]
+    fn mutable_string_set_and_read_back() {
+        let args = vec![SteelVal::IntV(3), SteelVal::CharV('a')];
+        let res = steel_make_mutable_string(&args).unwrap();
+        let mut s = MutableString::from_steelval(&res).unwrap();
+        assert_eq!(s.string_length(), 3);
+
+        s.string_set(1, 'λ').unwrap();
+        s.string_set(2, 'c').unwrap();
+        assert_eq!(s.string_ref(1).unwrap(), 'λ');
+        assert_eq!(s.to_immutable_string(), SteelVal::StringV("aλc".into()));
+    }
+
+    #[test]
+    fn mutable_string_set_out_of_range() {
+        let args = vec![SteelVal::IntV(2)];
+        let res = steel_make_mutable_string(&args).unwrap();
+        let mut s = MutableString::from_steelval(&res).unwrap();
+
+        let err = s.string_set(2, 'a').unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Generic);
+        assert!(s.string_ref(5).is_err());
+        assert_eq!(s.to_immutable_string(), SteelVal::StringV("\0\0".into()));
+    }
+
     #[test]
     fn string_append_test_normal() {
         let args = vec![
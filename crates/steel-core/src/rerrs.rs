@@ -1,5 +1,9 @@
 use crate::steel_vm::vm::DehydratedCallContext;
-use crate::{parser::parser::ParseError, rvals::Custom, steel_vm::vm::DehydratedStackTrace};
+use crate::{
+    parser::parser::ParseError,
+    rvals::{Custom, SteelVal},
+    steel_vm::vm::DehydratedStackTrace,
+};
 use std::{convert::Infallible, fmt::Formatter};
 // use thiserror::Error;
 
@@ -20,6 +24,15 @@ struct Repr {
     pub span: Option<Span>,
     // pub source: Option<Rc<PathBuf>>,
     pub stack_trace: Option<DehydratedStackTrace>,
+    pub error_object: Option<Box<ErrorObject>>,
+}
+
+/// What `error` was called with, before the message and irritants were joined into the
+/// message of the error.
+#[derive(Clone, Debug, PartialEq)]
+struct ErrorObject {
+    message: String,
+    irritants: Vec<SteelVal>,
 }
 
 impl Repr {
@@ -84,6 +97,7 @@ impl From<std::io::Error> for Repr {
             span: None,
             // source: None,
             stack_trace: None,
+            error_object: None,
         }
     }
 }
@@ -108,6 +122,7 @@ impl From<Infallible> for Repr {
             span: None,
             // source: None,
             stack_trace: None,
+            error_object: None,
         }
     }
 }
@@ -127,6 +142,13 @@ impl From<ParseError> for Repr {
             ParseError::IncompleteString(_, s, source) => (Some(*s), source),
             ParseError::SyntaxError(_, s, source) => (Some(*s), source),
             ParseError::ArityMismatch(_, s, source) => (Some(*s), source),
+            ParseError::UnexpectedClose(_, s, source) => (Some(*s), source),
+            ParseError::MissingCloseParen {
+                open_span, source, ..
+            } => (Some(*open_span), source),
+            ParseError::UnexpectedToken { span, source, .. } => (Some(*span), source),
+            ParseError::MisplacedSplice(s, source) => (Some(*s), source),
+            ParseError::MisplacedDot(s, source) => (Some(*s), source),
         };
 
         Repr {
@@ -135,6 +157,7 @@ impl From<ParseError> for Repr {
             span,
             // source: source.clone(),
             stack_trace: None,
+            error_object: None,
         }
     }
 }
@@ -174,6 +197,33 @@ impl SteelErr {
         self.repr.kind = kind;
     }
 
+    pub fn message(&self) -> &str {
+        &self.repr.message
+    }
+
+    /// Keeps the message and irritants that make up the message of the error apart, for
+    /// `error-object-message` and `error-object-irritants`.
+    pub fn with_irritants(mut self, message: String, irritants: Vec<SteelVal>) -> Self {
+        self.repr.error_object = Some(Box::new(ErrorObject { message, irritants }));
+        self
+    }
+
+    /// The message without the irritants, which is the whole message for errors that
+    /// weren't raised with any.
+    pub fn object_message(&self) -> &str {
+        match &self.repr.error_object {
+            Some(object) => &object.message,
+            None => &self.repr.message,
+        }
+    }
+
+    pub fn irritants(&self) -> &[SteelVal] {
+        match &self.repr.error_object {
+            Some(object) => &object.irritants,
+            None => &[],
+        }
+    }
+
     pub fn prepend_message(&mut self, message: &str) {
         self.repr.message.insert_str(0, &message)
     }
@@ -186,6 +236,7 @@ impl SteelErr {
                 span: None,
                 // source: None,
                 stack_trace: None,
+                error_object: None,
             }),
         }
     }
@@ -35,7 +35,7 @@ use std::{
     io::Write,
     ops::Deref,
     pin::Pin,
-    rc::Rc,
+    rc::{Rc, Weak},
     result,
     sync::{Arc, Mutex},
     task::Context,
@@ -1619,6 +1619,145 @@ pub fn value_into_iterator(val: SteelVal) -> Option<SteelVal> {
 
 thread_local! {
     pub static ITERATOR_FINISHED: SteelVal = SteelVal::SymbolV("done".into());
+
+    static SYMBOLS: RefCell<SymbolTable> = RefCell::new(SymbolTable::default());
+}
+
+/// The id of a symbol's name, see [`SteelVal::symbol_id`]. Ids are only handed out
+/// while a symbol with that name is alive. Once every symbol with the name has been
+/// dropped, the id stops resolving, even after its slot is reused for another name.
+/// Ids belong to the thread that handed them out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SymbolId {
+    index: u32,
+    // Bumped every time the slot at `index` is freed, so stale ids don't match its new name
+    generation: u32,
+}
+
+impl SymbolId {
+    /// The symbol with this id, unless every symbol with its name has been dropped.
+    pub fn resolve(self) -> Option<SteelVal> {
+        SYMBOLS.with(|symbols| {
+            symbols
+                .borrow()
+                .get(self)
+                .map(|name| SteelVal::SymbolV(SteelString(name)))
+        })
+    }
+}
+
+#[derive(Default)]
+struct SymbolSlot {
+    generation: u32,
+    // `None` while the slot is free to reuse
+    name: Option<Weak<String>>,
+}
+
+/// The symbols made through `SteelVal::symbol`, so that symbols with the same name share
+/// an allocation and `eq?` stays a pointer comparison. The table only holds weak
+/// references, and the entries of dropped symbols are cleared as it grows.
+#[derive(Default)]
+struct SymbolTable {
+    ids: fxhash::FxHashMap<String, SymbolId>,
+    // Indexed by `SymbolId::index`
+    slots: Vec<SymbolSlot>,
+    free: Vec<u32>,
+    // The number of entries at which to next clear out the dropped symbols
+    sweep_at: usize,
+}
+
+impl SymbolTable {
+    /// The allocation of the live symbol with `id`.
+    fn get(&self, id: SymbolId) -> Option<Rc<String>> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)?
+            .name
+            .as_ref()?
+            .upgrade()
+    }
+
+    /// The id and allocation of the live symbol called `name`.
+    fn live(&self, name: &str) -> Option<(SymbolId, Rc<String>)> {
+        let id = *self.ids.get(name)?;
+
+        Some((id, self.get(id)?))
+    }
+
+    /// The live symbol called `name`, allocating it if there is none.
+    fn intern(&mut self, name: &str) -> SteelString {
+        if let Some((_, symbol)) = self.live(name) {
+            return SteelString(symbol);
+        }
+
+        let symbol = SteelString::from(name);
+        self.insert(&symbol);
+        symbol
+    }
+
+    /// The id of `symbol`'s name, which becomes the shared symbol for the name when there
+    /// is no live one.
+    fn id(&mut self, symbol: &SteelString) -> SymbolId {
+        match self.live(symbol.as_str()) {
+            Some((id, _)) => id,
+            None => self.insert(symbol),
+        }
+    }
+
+    fn insert(&mut self, symbol: &SteelString) -> SymbolId {
+        let name = Some(Rc::downgrade(&symbol.0));
+
+        if let Some(&id) = self.ids.get(symbol.as_str()) {
+            self.slots[id.index as usize].name = name;
+            return id;
+        }
+
+        if self.ids.len() >= self.sweep_at {
+            self.sweep();
+        }
+
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(SymbolSlot::default());
+                u32::try_from(self.slots.len() - 1).expect("ran out of symbol ids")
+            }
+        };
+
+        let slot = &mut self.slots[index as usize];
+        slot.name = name;
+
+        let id = SymbolId {
+            index,
+            generation: slot.generation,
+        };
+
+        self.ids.insert(symbol.as_str().to_owned(), id);
+        id
+    }
+
+    fn sweep(&mut self) {
+        let slots = &mut self.slots;
+        let free = &mut self.free;
+
+        self.ids.retain(|_, id| {
+            let slot = &mut slots[id.index as usize];
+            let alive = slot
+                .name
+                .as_ref()
+                .map_or(false, |name| name.strong_count() > 0);
+
+            if !alive {
+                slot.name = None;
+                slot.generation = slot.generation.wrapping_add(1);
+                free.push(id.index);
+            }
+
+            alive
+        });
+
+        self.sweep_at = (self.ids.len() * 2).max(64);
+    }
 }
 
 pub fn iterator_next(args: &[SteelVal]) -> Result<SteelVal> {
@@ -1642,6 +1781,7 @@ impl SteelVal {
             (IntV(l), IntV(r)) => l == r,
             (NumV(l), NumV(r)) => l == r,
             (BoolV(l), BoolV(r)) => l == r,
+            (CharV(l), CharV(r)) => l == r,
             (VectorV(l), VectorV(r)) => Gc::ptr_eq(&l.0, &r.0),
             (Void, Void) => true,
             (StringV(l), StringV(r)) => Rc::ptr_eq(l, r),
@@ -1677,7 +1817,8 @@ impl Hash for SteelVal {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
             BoolV(b) => b.hash(state),
-            NumV(n) => n.to_string().hash(state),
+            // `0.0` and `-0.0` are equal, so they have to hash the same
+            NumV(n) => (if *n == 0.0 { 0.0 } else { *n }).to_bits().hash(state),
             IntV(i) => i.hash(state),
             Rational(f) => f.hash(state),
             BigNum(n) => n.hash(state),
@@ -1729,11 +1870,18 @@ impl SteelVal {
         matches!(self, SteelVal::FutureV(_))
     }
 
+    /// Whether this value can be used as a key in a hash map or set. These are the values
+    /// whose hash is consistent with `equal?`, which excludes mutable values like mutable
+    /// vectors, whose contents could change after they've been inserted.
     pub fn is_hashable(&self) -> bool {
         matches!(
             self,
             BoolV(_)
                 | IntV(_)
+                | NumV(_)
+                | Rational(_)
+                | BigNum(_)
+                | BigRational(_)
                 | CharV(_)
                 // | Pair(_)
                 | VectorV(_)
@@ -1769,6 +1917,23 @@ impl SteelVal {
 }
 
 impl SteelVal {
+    /// The symbol called `name`. While a symbol made this way is alive, every call with
+    /// the same name returns the same allocation, so these symbols are `eq?` exactly when
+    /// their names match.
+    pub fn symbol(name: &str) -> SteelVal {
+        SYMBOLS.with(|symbols| SteelVal::SymbolV(symbols.borrow_mut().intern(name)))
+    }
+
+    /// The id of a symbol's name, which is cheap to compare and hash. Symbols with the
+    /// same name have the same id for as long as one of them is alive, and
+    /// [`SymbolId::resolve`] turns the id back into a symbol.
+    pub fn symbol_id(&self) -> Option<SymbolId> {
+        match self {
+            SteelVal::SymbolV(s) => Some(SYMBOLS.with(|symbols| symbols.borrow_mut().id(s))),
+            _ => None,
+        }
+    }
+
     // pub fn res_iterator
 
     pub fn list_or_else<E, F: FnOnce() -> E>(
@@ -2235,6 +2400,24 @@ mod or_else_tests {
         );
     }
 
+    #[test]
+    fn floats_display_shortest_round_trip() {
+        let cases = [
+            (0.1, "0.1"),
+            (1.0, "1.0"),
+            (-0.0, "-0.0"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (std::f64::consts::PI, "3.141592653589793"),
+            (1e300, "1e300"),
+        ];
+
+        for (x, expected) in cases {
+            let printed = SteelVal::NumV(x).to_string();
+            assert_eq!(printed, expected);
+            assert_eq!(printed.parse::<f64>(), Ok(x));
+        }
+    }
+
     #[test]
     fn number_cmp() {
         let less_cases = [
@@ -2271,3 +2454,130 @@ mod or_else_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod hash_tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_of(value: &SteelVal) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn equal_values_hash_equally() {
+        let pairs = [
+            (SteelVal::IntV(10), SteelVal::IntV(10)),
+            (SteelVal::NumV(0.0), SteelVal::NumV(-0.0)),
+            (SteelVal::NumV(1.5), SteelVal::NumV(1.5)),
+            (SteelVal::BoolV(true), SteelVal::BoolV(true)),
+            (SteelVal::CharV('a'), SteelVal::CharV('a')),
+            (
+                SteelVal::StringV("foo".into()),
+                SteelVal::StringV("foo".into()),
+            ),
+            (
+                SteelVal::SymbolV("foo".into()),
+                SteelVal::SymbolV("foo".into()),
+            ),
+            (
+                SteelVal::ListV(vec![SteelVal::IntV(1), SteelVal::IntV(2)].into()),
+                SteelVal::ListV(vec![SteelVal::IntV(1), SteelVal::IntV(2)].into()),
+            ),
+            (
+                im_rc::vector![SteelVal::IntV(1)].into(),
+                im_rc::vector![SteelVal::IntV(1)].into(),
+            ),
+        ];
+
+        for (left, right) in pairs {
+            assert!(left.is_hashable());
+            assert_eq!(left, right);
+            assert_eq!(hash_of(&left), hash_of(&right));
+        }
+
+        assert_ne!(
+            hash_of(&SteelVal::StringV("foo".into())),
+            hash_of(&SteelVal::SymbolV("foo".into()))
+        );
+    }
+
+    #[test]
+    fn equal_values_are_interchangeable_keys() {
+        let mut map = std::collections::HashMap::new();
+
+        map.insert(
+            SteelVal::ListV(vec![SteelVal::IntV(1), SteelVal::CharV('b')].into()),
+            "list",
+        );
+        map.insert(SteelVal::NumV(0.0), "zero");
+
+        assert_eq!(
+            map.get(&SteelVal::ListV(
+                vec![SteelVal::IntV(1), SteelVal::CharV('b')].into()
+            )),
+            Some(&"list")
+        );
+        assert_eq!(map.get(&SteelVal::NumV(-0.0)), Some(&"zero"));
+    }
+}
+
+#[cfg(test)]
+mod symbol_tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_ids_are_stable_and_distinct() {
+        let foo = SteelVal::symbol("symbol-tests-foo");
+        let bar = SteelVal::symbol("symbol-tests-bar");
+        let other_foo = SteelVal::SymbolV("symbol-tests-foo".into());
+
+        let id = foo.symbol_id().unwrap();
+        assert_eq!(foo.symbol_id(), Some(id));
+        assert_eq!(SteelVal::symbol("symbol-tests-foo").symbol_id(), Some(id));
+        assert_eq!(other_foo.symbol_id(), Some(id));
+        assert_ne!(bar.symbol_id(), Some(id));
+        assert_eq!(SteelVal::IntV(1).symbol_id(), None);
+
+        match (id.resolve(), &foo) {
+            (Some(SteelVal::SymbolV(resolved)), SteelVal::SymbolV(foo)) => {
+                assert!(Rc::ptr_eq(&resolved, foo))
+            }
+            (resolved, _) => panic!("expected the symbol back, got {resolved:?}"),
+        }
+    }
+
+    #[test]
+    fn test_dropped_symbols_are_evicted() {
+        let id = SteelVal::symbol("symbol-tests-dropped")
+            .symbol_id()
+            .unwrap();
+        assert!(id.resolve().is_none());
+
+        // Enough new names to clear out the dropped ones
+        let live: Vec<_> = (0..256)
+            .map(|i| SteelVal::symbol(&format!("symbol-tests-{i}")))
+            .collect();
+
+        SYMBOLS.with(|symbols| {
+            let symbols = symbols.borrow();
+            assert!(!symbols.ids.contains_key("symbol-tests-dropped"));
+            assert!(symbols.ids.len() <= 2 * live.len());
+        });
+
+        // The dropped symbol's slot has gone to one of the new names, which its id
+        // doesn't resolve to
+        assert!(live
+            .iter()
+            .any(|symbol| symbol.symbol_id().unwrap().index == id.index));
+        assert!(id.resolve().is_none());
+
+        let unknown = SymbolId {
+            index: u32::MAX,
+            generation: 0,
+        };
+        assert!(unknown.resolve().is_none());
+    }
+}
@@ -170,13 +170,12 @@ impl CycleDetector {
             Complex(x) => write!(f, "{}", x.as_ref()),
             StringV(s) => write!(f, "{s:?}"),
             ByteVector(b) => write!(f, "{:?}", b.vec.borrow()),
-            CharV(c) => {
-                if c.is_ascii_control() {
-                    write!(f, "{}", c)
-                } else {
-                    write!(f, "#\\{c}")
-                }
-            }
+            // Written the way the reader reads it back, `display` writes the bare character
+            CharV(c) => write!(
+                f,
+                "{}",
+                steel_parser::tokens::TokenType::<String>::CharacterLiteral(*c)
+            ),
             Pair(p) => {
                 write!(f, "({} . {})", p.car(), p.cdr())
             }
@@ -298,7 +297,26 @@ impl CycleDetector {
             MutFunc(_) => write!(f, "#<function>"),
             BuiltIn(_) => write!(f, "#<function>"),
             ReducerV(_) => write!(f, "#<reducer>"),
-            MutableVector(v) => write!(f, "{:?}", v.get()),
+            MutableVector(v) => match (self.cycles.get(&v.as_ptr_usize()).copied(), format_type) {
+                (Some(id), FormatType::Normal) => {
+                    write!(f, "#{id}#")
+                }
+                _ => {
+                    write!(f, "[")?;
+
+                    let values = v.get();
+                    let mut iter = values.iter().peekable();
+
+                    while let Some(item) = iter.next() {
+                        self.format_with_cycles(item, f, FormatType::Normal)?;
+                        if iter.peek().is_some() {
+                            write!(f, ", ")?
+                        }
+                    }
+
+                    write!(f, "]")
+                }
+            },
             SyntaxObject(s) => {
                 if let Some(raw) = &s.raw {
                     write!(f, "#<syntax:{:?} {:?}>", s.span, raw)
@@ -1698,6 +1716,15 @@ impl<'a> RecursiveEqualityHandler<'a> {
                         continue;
                     }
 
+                    // Mutable values can form cycles, so once both sides have been
+                    // seen before we've come back around, and can assume they're equal.
+                    let left_unvisited = self.should_visit(l.as_ptr_usize());
+                    let right_unvisited = self.should_visit(r.as_ptr_usize());
+
+                    if !left_unvisited && !right_unvisited {
+                        continue;
+                    }
+
                     self.left.visit_heap_allocated(l);
                     self.right.visit_heap_allocated(r);
 
@@ -1709,6 +1736,15 @@ impl<'a> RecursiveEqualityHandler<'a> {
                         continue;
                     }
 
+                    // Mutable values can form cycles, so once both sides have been
+                    // seen before we've come back around, and can assume they're equal.
+                    let left_unvisited = self.should_visit(l.as_ptr_usize());
+                    let right_unvisited = self.should_visit(r.as_ptr_usize());
+
+                    if !left_unvisited && !right_unvisited {
+                        continue;
+                    }
+
                     self.left.visit_mutable_vector(l);
                     self.right.visit_mutable_vector(r);
 
@@ -450,7 +450,8 @@
     [(FlatContract? x) (FlatContract-name x)]
     [(FunctionContract? x) (string->symbol (contract->string x))]
     [else
-     (let ([lookup (function-name x)]) (if (string? lookup) (string->symbol lookup) '#<function>))]))
+     (let ([lookup (function-name x)])
+       (if (string? lookup) (string->symbol lookup) (string->symbol "#<function>")))]))
 
 ;; Like listof, however requires that the list is non empty as well
 (define (non-empty-listof pred)
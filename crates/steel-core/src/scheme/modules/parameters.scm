@@ -3,7 +3,23 @@
          call/cc
          call-with-current-continuation
          make-parameter
-         continuation?)
+         continuation?
+         with-exception-handler
+         raise
+         raise-continuable
+         &error
+         &message
+         &irritants
+         make-error
+         make-message-condition
+         make-irritants-condition
+         condition
+         condition?
+         error?
+         message-condition?
+         irritants-condition?
+         condition-message
+         condition-irritants)
 
 ;;;;;; Parameters ;;;;;
 
@@ -165,3 +181,121 @@
       (set! winders (cdr winders))
       (out)
       ans*)))
+
+;;;;;; Conditions ;;;;;
+
+;; The simple condition types. A condition is made up of any number of them, so that
+;; for example an error with a message is both `error?` and `message-condition?`.
+(struct &error ())
+(struct &message (message))
+(struct &irritants (irritants))
+
+(struct Condition (components)
+  #:printer (lambda (obj printer) (simple-display "#<condition>")))
+
+(define (make-error)
+  (&error))
+
+(define (make-message-condition message)
+  (&message message))
+
+(define (make-irritants-condition irritants)
+  (&irritants irritants))
+
+(define (condition . components)
+  (Condition components))
+
+(define (condition? value)
+  (Condition? value))
+
+(define (find-component value pred)
+  (if (Condition? value)
+      (let loop ([components (Condition-components value)])
+        (cond
+          [(null? components) #f]
+          [(pred (car components)) (car components)]
+          [else (loop (cdr components))]))
+      #f))
+
+(define (error? value)
+  (if (find-component value &error?) #t #f))
+
+(define (message-condition? value)
+  (if (find-component value &message?) #t #f))
+
+(define (irritants-condition? value)
+  (if (find-component value &irritants?) #t #f))
+
+(define (condition-message value)
+  (let ([component (find-component value &message?)])
+    (if component
+        (&message-message component)
+        (error "condition-message expects a condition with a message, found:" value))))
+
+(define (condition-irritants value)
+  (let ([component (find-component value &irritants?)])
+    (if component
+        (&irritants-irritants component)
+        (error "condition-irritants expects a condition with irritants, found:" value))))
+
+;; Errors raised by the runtime, or with `error`, reach handlers as a condition carrying
+;; their message and irritants.
+(define (error-object->condition err)
+  (condition (make-error)
+             (make-message-condition (trim (error-object-message err)))
+             (make-irritants-condition (error-object-irritants err))))
+
+;;;;;; Exception handlers ;;;;;
+
+;; The installed handlers, innermost first.
+(define current-handlers (make-parameter '()))
+
+;; The continuation of the outermost `with-exception-handler`, which an exception that no
+;; handler is left for escapes to before it becomes an error. Otherwise the error would
+;; reach the handlers it has already been through a second time.
+(define exception-escape (make-parameter #f))
+
+;; Calls `thunk` with `handler` installed. `raise` and errors abandon the thunk, and
+;; call the handler with the outer handlers installed. If the handler returns, a
+;; secondary error is raised to the outer handlers. `raise-continuable` instead returns
+;; the value of the handler to where it was raised from.
+(define (with-exception-handler handler thunk)
+  ((call/cc (lambda (k)
+              (let ([result (parameterize ([current-handlers (cons handler (current-handlers))]
+                                           [exception-escape (or (exception-escape) k)])
+                              (call-with-exception-handler raise-error-object thunk))])
+                (lambda () result))))))
+
+(define (raise-error-object err)
+  (raise (error-object->condition err)))
+
+;; Calls the innermost of `handlers` on `obj` with the outer ones installed, so that
+;; anything it raises, including errors from the runtime, goes to them.
+(define (call-handler handlers obj)
+  (parameterize ([current-handlers (cdr handlers)])
+    (call-with-exception-handler raise-error-object (lambda () ((car handlers) obj)))))
+
+(define (uncaught-exception obj)
+  (if (message-condition? obj)
+      (apply error
+             (condition-message obj)
+             (if (irritants-condition? obj) (condition-irritants obj) '()))
+      (error "uncaught exception:" obj)))
+
+(define (raise obj)
+  (let ([handlers (current-handlers)] [escape (exception-escape)])
+    (cond
+      [(pair? handlers)
+       (call-handler handlers obj)
+       (parameterize ([current-handlers (cdr handlers)])
+         (raise (condition (make-error)
+                           (make-message-condition "exception handler returned from raise")
+                           (make-irritants-condition (list obj)))))]
+      [escape (escape (lambda () (uncaught-exception obj)))]
+      [else (uncaught-exception obj)])))
+
+(define (raise-continuable obj)
+  (let ([handlers (current-handlers)])
+    (if (null? handlers)
+        (raise obj)
+        (call-handler handlers obj))))
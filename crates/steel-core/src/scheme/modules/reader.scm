@@ -8,13 +8,17 @@
 
 (define (read . port)
 
-  (if (null? port)
+  (cond
+    [(null? port) (read-impl)]
+
+    ;; File and string ports are read directly, which leaves them just past the datum
+    [(#%seekable-port? (car port)) (read-from-port (car port))]
 
-      (read-impl)
+    [else
 
-      (parameterize ([current-input-port (car port)])
+     (parameterize ([current-input-port (car port)])
 
-        (read-impl))))
+       (read-impl))]))
 
 (define (read-impl)
 
@@ -2289,4 +2289,337 @@ mod engine_api_tests {
             .compile_and_run_raw_program("(external-get-value-imm *external*)")
             .is_err());
     }
+
+    #[test]
+    fn test_unspecified_results_are_void() {
+        let mut engine = Engine::new();
+
+        let results = engine
+            .compile_and_run_raw_program("(define x 1) (set! x 2)")
+            .unwrap();
+
+        for result in results {
+            assert_eq!(result, SteelVal::Void);
+            assert_ne!(result, SteelVal::BoolV(false));
+            assert!(!matches!(result, SteelVal::ListV(_)));
+            assert_eq!(result.to_string(), "#<void>");
+        }
+    }
+
+    /// The values of the last `N` expressions in `script`.
+    fn last_results<const N: usize>(engine: &mut Engine, script: &'static str) -> [SteelVal; N] {
+        let mut results = engine.compile_and_run_raw_program(script).unwrap();
+        let start = results
+            .len()
+            .checked_sub(N)
+            .unwrap_or_else(|| panic!("expected at least {N} results, got {results:?}"));
+
+        results.split_off(start).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_cyclic_values_display_and_equality_terminate() {
+        let mut engine = Engine::new();
+
+        let [cycle, same, different] = &last_results::<3>(
+            &mut engine,
+            r#"
+                (define a (mutable-vector 1 0))
+                (vector-set! a 1 a)
+                (define b (mutable-vector 1 0))
+                (vector-set! b 1 b)
+                (define c (mutable-vector 2 0))
+                (vector-set! c 1 c)
+                a
+                (equal? a b)
+                (equal? a c)
+                "#,
+        );
+
+        assert!(cycle.to_string().contains("#0=[1, #0#]"));
+        assert_eq!(same, &SteelVal::BoolV(true));
+        assert_eq!(different, &SteelVal::BoolV(false));
+    }
+
+    #[test]
+    fn test_symbols_with_the_same_name_share_an_id() {
+        let mut engine = Engine::new();
+
+        let [a, b, c, same, from_string, different] = &last_results::<6>(
+            &mut engine,
+            r#"
+                (define (make-foo) 'foo)
+                'foo
+                (make-foo)
+                (string->symbol "foo")
+                (eq? 'foo (make-foo))
+                (eq? 'foo (string->symbol "foo"))
+                (eq? 'foo 'bar)
+                "#,
+        );
+
+        assert!(a.symbol_id().is_some());
+        assert_eq!(a.symbol_id(), b.symbol_id());
+        assert_eq!(a.symbol_id(), c.symbol_id());
+        assert_eq!(same, &SteelVal::BoolV(true));
+        assert_eq!(from_string, &SteelVal::BoolV(true));
+        assert_eq!(different, &SteelVal::BoolV(false));
+    }
+
+    #[test]
+    fn test_read_consecutive_data_from_string_port() {
+        let mut engine = Engine::new();
+
+        let [first, second, end, expected] = &last_results::<4>(
+            &mut engine,
+            r#"
+                (define port (open-input-string "(define x\n  (+ 1 2)) ; done\n\"two\""))
+                (read-from-port port)
+                (read-from-port port)
+                (read-from-port port)
+                '(define x (+ 1 2))
+                "#,
+        );
+
+        assert_eq!(first, expected);
+        assert_eq!(second, &SteelVal::StringV("two".into()));
+        assert_eq!(end, &SteelVal::SymbolV("eof".into()));
+    }
+
+    #[test]
+    fn test_read_from_port_stops_at_syntax_error() {
+        let mut engine = Engine::new();
+
+        engine
+            .compile_and_run_raw_program(
+                r#"(define port (open-input-string "(a . b c)\n(next\n  datum)"))"#,
+            )
+            .unwrap();
+
+        assert!(engine
+            .compile_and_run_raw_program("(read-from-port port)")
+            .is_err());
+
+        let results = engine
+            .compile_and_run_raw_program("(equal? (read-from-port port) '(next datum))")
+            .unwrap();
+
+        assert_eq!(results, vec![SteelVal::BoolV(true)]);
+    }
+
+    #[test]
+    fn test_char_equality_and_printing() {
+        let mut engine = Engine::new();
+
+        let [equality, printed] = &last_results::<2>(
+            &mut engine,
+            r#"
+                (list (eqv? #\a #\a) (equal? #\a #\a) (eq? #\a #\a) (eqv? #\a #\b))
+                (let ([port (open-output-string)])
+                  (write #\a port)
+                  (display #\a port)
+                  (write #\newline port)
+                  (display #\newline port)
+                  (get-output-string port))
+                "#,
+        );
+
+        assert_eq!(
+            equality,
+            &SteelVal::ListV(
+                vec![
+                    SteelVal::BoolV(true),
+                    SteelVal::BoolV(true),
+                    SteelVal::BoolV(true),
+                    SteelVal::BoolV(false),
+                ]
+                .into()
+            )
+        );
+        assert_eq!(printed, &SteelVal::StringV("#\\aa#\\NEWLINE\n".into()));
+    }
+
+    #[test]
+    fn test_unquote_splicing_in_quasiquoted_vector() {
+        let mut engine = Engine::new();
+
+        let [spliced, expected_spliced, nested, expected_nested] = &last_results::<4>(
+            &mut engine,
+            r#"
+                (define xs (list 2 3))
+                `#(1 ,@(list 2 3) 4)
+                (vector 1 2 3 4)
+                `(a #(b ,(car xs)))
+                (list 'a (vector 'b 2))
+                "#,
+        );
+
+        assert_eq!(spliced, expected_spliced);
+        assert_eq!(nested, expected_nested);
+    }
+
+    #[test]
+    fn test_typed_equality_predicates() {
+        let mut engine = Engine::new();
+
+        let [booleans, symbols] = &last_results::<2>(
+            &mut engine,
+            r#"
+                (list (boolean=? #t #t) (boolean=? #f #f #f) (boolean=? #t #f) (boolean=? #t #t #f))
+                (list (symbol=? 'a 'a) (symbol=? 'a 'a 'a) (symbol=? 'a 'b) (symbol=? 'a 'a 'b))
+                "#,
+        );
+
+        let expected = SteelVal::ListV(
+            vec![
+                SteelVal::BoolV(true),
+                SteelVal::BoolV(true),
+                SteelVal::BoolV(false),
+                SteelVal::BoolV(false),
+            ]
+            .into(),
+        );
+
+        assert_eq!(booleans, &expected);
+        assert_eq!(symbols, &expected);
+
+        assert!(engine
+            .compile_and_run_raw_program("(boolean=? #t 1)")
+            .is_err());
+        assert!(engine
+            .compile_and_run_raw_program("(symbol=? 'a \"a\")")
+            .is_err());
+    }
+
+    #[test]
+    fn test_deep_copy() {
+        let mut engine = Engine::new();
+
+        let [copied, cycle] = &last_results::<2>(
+            &mut engine,
+            r#"
+                (define original (mutable-vector (mutable-vector 1 2) (box 3) (list "a" (hash 'k 4))))
+                (define alias original)
+                (define copy (deep-copy original))
+                (vector-set! (mut-vector-ref copy 0) 0 10)
+                (set-box! (mut-vector-ref copy 1) 30)
+                (vector-set! alias 2 'shared)
+                (list (mut-vector-ref (mut-vector-ref original 0) 0)
+                      (unbox (mut-vector-ref original 1))
+                      (mut-vector-ref (mut-vector-ref copy 0) 0)
+                      (unbox (mut-vector-ref copy 1))
+                      (mut-vector-ref original 2)
+                      (equal? (mut-vector-ref copy 2) (list "a" (hash 'k 4))))
+                (define cycle (mutable-vector 1 2))
+                (vector-set! cycle 0 cycle)
+                (define cycle-copy (deep-copy cycle))
+                (list (eq? (mut-vector-ref cycle-copy 0) cycle-copy) (eq? cycle-copy cycle))
+                "#,
+        );
+
+        assert_eq!(
+            copied,
+            &SteelVal::ListV(
+                vec![
+                    SteelVal::IntV(1),
+                    SteelVal::IntV(3),
+                    SteelVal::IntV(10),
+                    SteelVal::IntV(30),
+                    SteelVal::SymbolV("shared".into()),
+                    SteelVal::BoolV(true),
+                ]
+                .into()
+            )
+        );
+        assert_eq!(
+            cycle,
+            &SteelVal::ListV(vec![SteelVal::BoolV(true), SteelVal::BoolV(false)].into())
+        );
+    }
+
+    #[test]
+    fn test_close_port() {
+        let mut engine = Engine::new();
+
+        let [predicates, .., still_port] = &last_results::<5>(
+            &mut engine,
+            r#"
+                (define input (open-input-string "(1 2) foo"))
+                (define output (open-output-string))
+                (list (port? input) (input-port? input) (output-port? input)
+                      (port? output) (input-port? output) (output-port? output)
+                      (port? "input"))
+                (close-port input)
+                (close-port input)
+                (close-port output)
+                (port? input)
+                "#,
+        );
+
+        assert_eq!(
+            predicates,
+            &SteelVal::ListV(
+                vec![
+                    SteelVal::BoolV(true),
+                    SteelVal::BoolV(true),
+                    SteelVal::BoolV(false),
+                    SteelVal::BoolV(true),
+                    SteelVal::BoolV(false),
+                    SteelVal::BoolV(true),
+                    SteelVal::BoolV(false),
+                ]
+                .into()
+            )
+        );
+        assert_eq!(still_port, &SteelVal::BoolV(true));
+
+        assert!(engine
+            .compile_and_run_raw_program("(raw-write-string output \"foo\")")
+            .is_err());
+
+        // The errors describe what was attempted on the port, whichever builtin did it
+        for (closed, operation) in [
+            ("(read-from-port input)", "read a datum from"),
+            ("(read-line-from-port input)", "read a line from"),
+            ("(get-output-string output)", "get the output string of"),
+        ] {
+            let error = engine.compile_and_run_raw_program(closed).unwrap_err();
+
+            assert!(
+                error
+                    .message()
+                    .contains(&format!("cannot {operation} a closed port")),
+                "{closed}: {error}"
+            );
+        }
+
+        // `display` writes through `raw-write-string`, which it shouldn't blame
+        let error = engine
+            .compile_and_run_raw_program("(display \"foo\" output)")
+            .unwrap_err();
+        assert!(error.message().contains("a closed port"), "{error}");
+        assert!(!error.message().contains("raw-write"), "{error}");
+    }
+
+    #[test]
+    fn test_register_closure_as_builtin() {
+        let mut engine = Engine::new();
+
+        let factor = 2;
+        engine.register_fn("double", move |x: isize| x * factor);
+
+        let results = engine
+            .compile_and_run_raw_program("(double 21) (map double (list 1 2 3))")
+            .unwrap();
+
+        assert_eq!(results[0], SteelVal::IntV(42));
+        assert_eq!(
+            Vec::<isize>::from_steelval(&results[1]).unwrap(),
+            vec![2, 4, 6]
+        );
+
+        assert!(engine
+            .compile_and_run_raw_program("(double \"not a number\")")
+            .is_err());
+    }
 }
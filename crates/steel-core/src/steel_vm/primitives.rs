@@ -41,7 +41,7 @@ use crate::{
         vm::threads::threading_module,
     },
     values::{
-        closed::HeapRef,
+        closed::{HeapRef, RootToken},
         functions::{attach_contract_struct, get_contract, LambdaMetadataTable},
         lists::List,
         structs::{
@@ -907,6 +907,50 @@ pub fn lte_primitive(args: &[SteelVal]) -> Result<SteelVal> {
     })))
 }
 
+/// Checks if all of the given booleans are the same
+///
+/// Requires that every argument is a boolean, and will otherwise raise an error.
+///
+/// # Examples
+/// ```scheme
+/// > (boolean=? #t #t) ;; => #t
+/// > (boolean=? #f #f #t) ;; => #f
+/// ```
+#[steel_derive::native(name = "boolean=?", constant = true, arity = "AtLeast(2)")]
+pub fn boolean_equals(args: &[SteelVal]) -> Result<SteelVal> {
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            SteelVal::BoolV(b) => values.push(*b),
+            _ => stop!(TypeMismatch => "boolean=? expects booleans, found: {}", arg),
+        }
+    }
+
+    Ok(SteelVal::BoolV(values.windows(2).all(|x| x[0] == x[1])))
+}
+
+/// Checks if all of the given symbols are the same
+///
+/// Requires that every argument is a symbol, and will otherwise raise an error.
+///
+/// # Examples
+/// ```scheme
+/// > (symbol=? 'a 'a) ;; => #t
+/// > (symbol=? 'a 'a 'b) ;; => #f
+/// ```
+#[steel_derive::native(name = "symbol=?", constant = true, arity = "AtLeast(2)")]
+pub fn symbol_equals(args: &[SteelVal]) -> Result<SteelVal> {
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            SteelVal::SymbolV(s) => values.push(s),
+            _ => stop!(TypeMismatch => "symbol=? expects symbols, found: {}", arg),
+        }
+    }
+
+    Ok(SteelVal::BoolV(values.windows(2).all(|x| x[0] == x[1])))
+}
+
 fn equality_module() -> BuiltInModule {
     let mut module = BuiltInModule::new("steel/equality");
     module
@@ -921,7 +965,9 @@ fn equality_module() -> BuiltInModule {
                 |a: &SteelVal, b: &SteelVal| a.ptr_eq(b)
             )),
         )
-        .register_native_fn_definition(NUMBER_EQUALITY_DEFINITION);
+        .register_native_fn_definition(NUMBER_EQUALITY_DEFINITION)
+        .register_native_fn_definition(BOOLEAN_EQUALS_DEFINITION)
+        .register_native_fn_definition(SYMBOL_EQUALS_DEFINITION);
 
     // TODO: Replace this with just numeric equality!
     // .register_value("=", SteelVal::FuncV(ensure_tonicity_two!(|a, b| a == b)));
@@ -1353,6 +1399,117 @@ fn make_mutable_box(ctx: &mut VmCore, args: &[SteelVal]) -> Option<Result<SteelV
     Some(Ok(SteelVal::HeapAllocated(allocated_var)))
 }
 
+/// Copies the given value along with all of the lists, vectors, strings, hash tables and
+/// boxes inside of it, so that mutating the copy never affects the original.
+///
+/// Everything else, like `define` or `set!`, shares structure: after `(define b a)`, `a`
+/// and `b` are the same vector, and `(vector-set! b 0 'x)` is visible through `a` as well.
+///
+/// (deep-copy value) -> any/c
+///
+/// # Examples
+/// ```scheme
+/// > (define a (mutable-vector (mutable-vector 1 2) 3))
+/// > (define b (deep-copy a))
+/// > (vector-set! (mut-vector-ref b 0) 0 10)
+/// > (mut-vector-ref (mut-vector-ref a 0) 0) ;; => 1
+/// ```
+fn deep_copy(ctx: &mut VmCore, args: &[SteelVal]) -> Option<Result<SteelVal>> {
+    if args.len() != 1 {
+        builtin_stop!(ArityMismatch => "deep-copy expects 1 argument, found: {}", args.len());
+    }
+
+    let mut copies = FxHashMap::default();
+    let mut roots = Vec::new();
+
+    Some(Ok(deep_copy_value(ctx, &args[0], &mut copies, &mut roots)))
+}
+
+// Mutable containers are copied only once, keyed by their address, so that values shared
+// between or cycling through them are shared the same way in the copy. The new containers
+// are rooted until the whole copy is done, since nothing else refers to them yet.
+fn deep_copy_value(
+    ctx: &mut VmCore,
+    value: &SteelVal,
+    copies: &mut FxHashMap<usize, SteelVal>,
+    roots: &mut Vec<RootToken>,
+) -> SteelVal {
+    let mut copy = |value: &SteelVal| deep_copy_value(ctx, value, copies, roots);
+
+    match value {
+        SteelVal::ListV(l) => SteelVal::ListV(l.iter().map(copy).collect()),
+        SteelVal::VectorV(v) => {
+            SteelVal::VectorV(Gc::new(v.iter().map(copy).collect::<im_rc::Vector<_>>()).into())
+        }
+        SteelVal::StringV(s) => SteelVal::StringV(s.as_str().into()),
+        SteelVal::HashMapV(hm) => SteelVal::HashMapV(
+            Gc::new(
+                hm.iter()
+                    .map(|(key, value)| (copy(key), copy(value)))
+                    .collect::<im_rc::HashMap<_, _>>(),
+            )
+            .into(),
+        ),
+        SteelVal::HashSetV(hs) => {
+            SteelVal::HashSetV(Gc::new(hs.iter().map(copy).collect::<im_rc::HashSet<_>>()).into())
+        }
+        SteelVal::MutableVector(v) => {
+            if let Some(existing) = copies.get(&v.as_ptr_usize()) {
+                return existing.clone();
+            }
+
+            let new = ctx.make_mutable_vector(Vec::new());
+            roots.push(new.mark_rooted());
+            copies.insert(v.as_ptr_usize(), new.clone());
+
+            let values = v
+                .get()
+                .iter()
+                .map(|value| deep_copy_value(ctx, value, copies, roots))
+                .collect();
+
+            if let SteelVal::MutableVector(new) = &new {
+                new.set_and_return(values);
+            }
+
+            new
+        }
+        SteelVal::HeapAllocated(b) => {
+            if let Some(existing) = copies.get(&b.as_ptr_usize()) {
+                return existing.clone();
+            }
+
+            let new = ctx.make_box(SteelVal::Void);
+            roots.push(new.mark_rooted());
+            copies.insert(b.as_ptr_usize(), new.clone());
+
+            let value = deep_copy_value(ctx, &b.get(), copies, roots);
+
+            if let SteelVal::HeapAllocated(new) = &new {
+                new.set_and_return(value);
+            }
+
+            new
+        }
+        SteelVal::Boxed(b) => {
+            let address = b.as_ptr() as usize;
+
+            if let Some(existing) = copies.get(&address) {
+                return existing.clone();
+            }
+
+            let new = Gc::new(RefCell::new(SteelVal::Void));
+            copies.insert(address, SteelVal::Boxed(new.clone()));
+
+            let value = deep_copy_value(ctx, &b.borrow(), copies, roots);
+            *new.borrow_mut() = value;
+
+            SteelVal::Boxed(new)
+        }
+        _ => value.clone(),
+    }
+}
+
 #[steel_derive::function(name = "unbox-strong")]
 pub fn unbox(value: &Gc<RefCell<SteelVal>>) -> SteelVal {
     value.borrow().clone()
@@ -1418,6 +1575,8 @@ fn meta_module() -> BuiltInModule {
         .register_value("error-with-span", error_with_src_loc())
         .register_value("raise-error-with-span", error_from_error_with_span())
         .register_value("raise-error", raise_error_from_error())
+        .register_value("error-object-message", error_object_message())
+        .register_value("error-object-irritants", error_object_irritants())
         .register_value("call/cc", SteelVal::BuiltIn(super::vm::call_cc))
         .register_value(
             "call-with-exception-handler",
@@ -1469,6 +1628,7 @@ fn meta_module() -> BuiltInModule {
         .register_native_fn_definition(SET_BOX_DEFINITION)
         .register_value("#%box", SteelVal::BuiltIn(make_mutable_box))
         .register_value("box", SteelVal::BuiltIn(make_mutable_box))
+        .register_value("deep-copy", SteelVal::BuiltIn(deep_copy))
         .register_native_fn_definition(SET_BOX_MUTABLE_DEFINITION)
         .register_native_fn_definition(UNBOX_MUTABLE_DEFINITION)
         .register_native_fn_definition(PLAIN_UNBOX_MUTABLE_DEFINITION)
@@ -1597,6 +1757,32 @@ pub fn raise_error_from_error() -> SteelVal {
     })
 }
 
+pub fn error_object_message() -> SteelVal {
+    SteelVal::FuncV(|args: &[SteelVal]| -> Result<SteelVal> {
+        if args.len() != 1 {
+            stop!(ArityMismatch => "error-object-message expects one argument - the error object")
+        }
+
+        let steel_error = SteelErr::from_steelval(&args[0])?;
+
+        Ok(SteelVal::StringV(steel_error.object_message().into()))
+    })
+}
+
+pub fn error_object_irritants() -> SteelVal {
+    SteelVal::FuncV(|args: &[SteelVal]| -> Result<SteelVal> {
+        if args.len() != 1 {
+            stop!(ArityMismatch => "error-object-irritants expects one argument - the error object")
+        }
+
+        let steel_error = SteelErr::from_steelval(&args[0])?;
+
+        Ok(SteelVal::ListV(
+            steel_error.irritants().iter().cloned().collect(),
+        ))
+    })
+}
+
 // Be able to introspect on the modules - probably just need to add a modules
 // field on the vm, or use a wrapped type with modules to find things
 // TODO: Add magic number for modules. - key to magic number, do pointer equality
@@ -72,6 +72,7 @@ test_harness_success! {
     docs,
     ellipses,
     empty,
+    exception_handlers,
     fib,
     generator,
     generic_execution,
@@ -120,6 +121,7 @@ test_harness_success! {
     transducer_over_streams,
     tree_traversal,
     trie_sort,
+    vector_literals,
     y_combinator,
 }
 
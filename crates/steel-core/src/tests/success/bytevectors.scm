@@ -25,3 +25,7 @@
 (assert-equal! (bytes-append (bytes 0 1 2) (bytes 3 4 5)) (bytes 0 1 2 3 4 5))
 
 (assert-equal! (list->bytes (list 0 1 2 3 4 5)) (bytes 0 1 2 3 4 5))
+
+(assert! (bytes? '#u8(1 2)))
+(assert-equal! '#u8(1 2) (bytes 1 2))
+(assert-equal! (cadr '(x #u8(1 255))) (bytes 1 255))
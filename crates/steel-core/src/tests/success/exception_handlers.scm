@@ -0,0 +1,85 @@
+(define-syntax assert-equal!
+  (syntax-rules ()
+    [(_ expected actual)
+     (let ([ok (equal? expected actual)])
+       (when (not ok)
+         (displayln "Expected value " expected " but got " actual ".")
+         (assert! ok)))]))
+
+;; A raised condition reaches the handler, which can read its parts
+(define caught
+  (call/cc (lambda (k)
+             (with-exception-handler (lambda (c)
+                                       (k (list (error? c)
+                                                (message-condition? c)
+                                                (condition-message c)
+                                                (condition-irritants c))))
+                                     (lambda ()
+                                       (raise (condition (make-error)
+                                                         (make-message-condition "bad value")
+                                                         (make-irritants-condition (list 1 2))))
+                                       'unreachable)))))
+
+(assert-equal! (list #t #t "bad value" (list 1 2)) caught)
+
+;; Anything can be raised, not just conditions
+(assert-equal! 'oops
+               (call/cc (lambda (k)
+                          (with-exception-handler (lambda (x) (k x)) (lambda () (+ 1 (raise 'oops)))))))
+
+;; Errors become conditions with their message and irritants
+(define from-error
+  (call/cc (lambda (k)
+             (with-exception-handler (lambda (c)
+                                       (k (list (error? c) (condition-message c) (condition-irritants c))))
+                                     (lambda () (error "something broke:" 1 'two))))))
+
+(assert-equal! (list #t "something broke:" (list 1 'two)) from-error)
+(assert! (condition? (call/cc (lambda (k)
+                                (with-exception-handler (lambda (c) (k c)) (lambda () (car '())))))))
+
+;; raise-continuable returns the value of the handler to where it was raised
+(assert-equal! 12
+               (with-exception-handler (lambda (x) (* x 2))
+                                       (lambda () (+ 2 (raise-continuable 5)))))
+
+;; The handler runs with the outer handlers installed
+(assert-equal! '(outer inner)
+               (call/cc (lambda (k)
+                          (with-exception-handler
+                           (lambda (x) (k (list 'outer x)))
+                           (lambda ()
+                             (with-exception-handler (lambda (x) (raise 'inner))
+                                                     (lambda () (raise 'first))))))))
+
+;; A handler returning from raise raises a secondary error to the outer handlers
+(assert-equal! '(#t first)
+               (call/cc (lambda (k)
+                          (with-exception-handler
+                           (lambda (c) (k (list (error? c) (car (condition-irritants c)))))
+                           (lambda ()
+                             (with-exception-handler (lambda (x) 'returned)
+                                                     (lambda () (raise 'first))))))))
+
+;; Once the form returns, its handler is no longer installed
+(assert-equal! 'after
+               (call/cc (lambda (k)
+                          (with-exception-handler
+                           (lambda (x) (k 'after))
+                           (lambda ()
+                             (with-exception-handler (lambda (x) 'inner) (lambda () 'done))
+                             (raise 'again))))))
+
+;; Nor once a continuation escapes from it
+(assert-equal! '(outer after-escape)
+               (call/cc (lambda (return)
+                          (with-exception-handler
+                           (lambda (x) (return (list 'outer x)))
+                           (lambda ()
+                             (call/cc (lambda (k)
+                                        (with-exception-handler (lambda (x) 'stale)
+                                                                (lambda () (k 'escaped)))))
+                             (raise-continuable 'after-escape))))))
+
+(assert! (not (error? (condition (make-message-condition "just a message")))))
+(assert! (not (condition? 'oops)))
@@ -0,0 +1,9 @@
+;; Outside of quoted data, a vector literal builds a vector out of its quoted elements
+(assert! (vector? #(1 2)))
+(assert! (equal? #(1 a) (vector 1 'a)))
+
+;; In quoted data, it is a vector datum rather than a call to `vector`
+(assert! (vector? '#(1 2)))
+(assert! (equal? '#(1 2) (vector 1 2)))
+(assert! (vector? (cadr '(x #(1 2)))))
+(assert! (equal? '(x #(a (b))) (list 'x (vector 'a '(b)))))
@@ -3,7 +3,7 @@ use std::fs::OpenOptions;
 use std::io;
 use std::io::prelude::*;
 use std::io::Cursor;
-use std::io::{BufReader, BufWriter, Stdin, Stdout};
+use std::io::{BufReader, BufWriter, SeekFrom, Stdin, Stdout};
 use std::process::ChildStdin;
 use std::process::ChildStdout;
 use std::sync::Arc;
@@ -13,13 +13,15 @@ use std::sync::Mutex;
 // use utils::chars::Chars;
 // use utils::{new_rc_ref_cell, RcRefCell};
 
+use crate::parser::parser::{ParseError, Parser};
+use crate::parser::tryfrom_visitor::TryFromExprKindForSteelVal;
 use crate::rerrs;
-use crate::rvals::Result;
+use crate::rvals::{Result, SteelVal};
 use crate::SteelErr;
 
 // use crate::rvals::{new_rc_ref_cell, RcRefSteelVal};
 
-use std::cell::RefCell;
+use std::cell::{RefCell, RefMut};
 use std::rc::Rc;
 
 // pub<T> type RcRefCell: Rc<RcRefCell<T>>;
@@ -147,11 +149,55 @@ macro_rules! port_read_str_fn(
     }};
 );
 
+// Reads lines until they hold a complete datum, then rewinds `br` to just after it so
+// that whatever follows is left for the next read. The text is only parsed again once it
+// has doubled in length, which keeps the work linear in the size of the datum.
+fn read_datum<R: BufRead + Seek>(br: &mut R) -> Result<Option<SteelVal>> {
+    let mut text = String::new();
+    let mut parsed_len = 0;
+
+    loop {
+        let at_eof = br.read_line(&mut text)? == 0;
+
+        if !at_eof && text.len() < 2 * parsed_len {
+            continue;
+        }
+        parsed_len = text.len();
+
+        let mut parser = Parser::new_flat(&text, None);
+
+        match parser.next() {
+            Some(Ok(expr)) => {
+                let unread = text.len() - parser.offset();
+                br.seek(SeekFrom::Current(-(unread as i64)))?;
+
+                return TryFromExprKindForSteelVal::try_from_expr_kind_quoted(expr).map(Some);
+            }
+            None if at_eof => return Ok(None),
+            // The datum might continue on the next line
+            Some(Err(e)) if !at_eof && is_incomplete(&e) => {}
+            Some(Err(e)) => return Err(e.into()),
+            None => {}
+        }
+    }
+}
+
+// Whether `error` only says that the input ended before the datum did.
+fn is_incomplete(error: &ParseError) -> bool {
+    matches!(
+        error,
+        ParseError::UnexpectedEOF(_)
+            | ParseError::MissingCloseParen { .. }
+            | ParseError::IncompleteString(..)
+    )
+}
+
 impl SteelPortRepr {
     pub fn read_line(&mut self) -> Result<(usize, String)> {
         match self {
             SteelPortRepr::FileInput(_, br) => port_read_str_fn!(br, read_line),
             SteelPortRepr::StdInput(br) => port_read_str_fn!(br, read_line),
+            SteelPortRepr::StringInput(br) => port_read_str_fn!(br, read_line),
 
             SteelPortRepr::ChildStdOutput(br) => {
                 // let buf_reader = BufReader::new(br.borrow_mut().as_mut());
@@ -169,6 +215,23 @@ impl SteelPortRepr {
         }
     }
 
+    /// Reads the next datum, or `None` if only whitespace and comments are left.
+    pub fn read_datum(&mut self) -> Result<Option<SteelVal>> {
+        match self {
+            SteelPortRepr::FileInput(_, br) => read_datum(br),
+            SteelPortRepr::StringInput(br) => read_datum(br),
+            _x => stop!(Generic => "read-from-port only supports file and string ports"),
+        }
+    }
+
+    /// Whether [`SteelPortRepr::read_datum`] can read from this port.
+    pub fn is_seekable(&self) -> bool {
+        matches!(
+            self,
+            SteelPortRepr::FileInput(_, _) | SteelPortRepr::StringInput(_)
+        )
+    }
+
     pub fn flush(&mut self) -> Result<()> {
         match self {
             SteelPortRepr::FileOutput(_, s) => Ok(s.flush()?),
@@ -286,7 +349,10 @@ impl SteelPortRepr {
     pub fn is_input(&self) -> bool {
         matches!(
             self,
-            SteelPortRepr::FileInput(_, _) | SteelPortRepr::StdInput(_)
+            SteelPortRepr::FileInput(_, _)
+                | SteelPortRepr::StdInput(_)
+                | SteelPortRepr::ChildStdOutput(_)
+                | SteelPortRepr::StringInput(_)
         )
     }
 
@@ -295,6 +361,8 @@ impl SteelPortRepr {
             self,
             SteelPortRepr::FileOutput(_, _)
                 | SteelPortRepr::StdOutput(_)
+                | SteelPortRepr::ChildStdInput(_)
+                | SteelPortRepr::StringOutput(_)
                 | SteelPortRepr::DynWriter(_)
         )
     }
@@ -321,6 +389,15 @@ impl SteelPortRepr {
         }
     }
 
+    pub fn is_closed(&self) -> bool {
+        matches!(self, SteelPortRepr::Closed)
+    }
+
+    /// Closes the port, whichever kind it is. Closing a closed port does nothing.
+    pub fn close(&mut self) {
+        *self = SteelPortRepr::Closed;
+    }
+
     pub fn close_output_port(&mut self) -> Result<()> {
         match self {
             SteelPortRepr::FileOutput(_, _) | SteelPortRepr::StdOutput(_) => {
@@ -378,8 +455,21 @@ impl SteelPort {
     //
     // Read functions
     //
+    // The port to read from or write to, which fails once it's closed. The error describes
+    // `operation` rather than naming a builtin, since several builtins end up here, like
+    // `display` and `write` going through `raw-write-string`.
+    fn open(&self, operation: &str) -> Result<RefMut<'_, SteelPortRepr>> {
+        let port = self.port.borrow_mut();
+
+        if port.is_closed() {
+            stop!(Generic => "cannot {} a closed port", operation);
+        }
+
+        Ok(port)
+    }
+
     pub fn read_line(&self) -> Result<(usize, String)> {
-        self.port.borrow_mut().read_line()
+        self.open("read a line from")?.read_line()
     }
 
     // TODO: Implement the rest of the flush methods
@@ -388,26 +478,30 @@ impl SteelPort {
     }
 
     pub fn read_all_str(&self) -> Result<(usize, String)> {
-        self.port.borrow_mut().read_all_str()
+        self.open("read from")?.read_all_str()
     }
 
     pub fn read_char(&self) -> Result<(usize, char)> {
-        self.port.borrow_mut().read_char()
+        self.open("read a character from")?.read_char()
+    }
+
+    pub fn read_datum(&self) -> Result<Option<SteelVal>> {
+        self.open("read a datum from")?.read_datum()
     }
 
     pub fn write_char(&self, c: char) -> Result<()> {
-        self.port.borrow_mut().write_char(c)
+        self.open("write a character to")?.write_char(c)
     }
 
     //
     // Write functions
     //
     pub fn write_string(&self, string: &str) -> Result<()> {
-        self.port.borrow_mut().write_string(string)
+        self.open("write to")?.write_string(string)
     }
 
     pub fn write_string_line(&self, string: &str) -> Result<()> {
-        self.port.borrow_mut().write_string_line(string)
+        self.open("write a line to")?.write_string_line(string)
     }
 
     //
@@ -425,6 +519,14 @@ impl SteelPort {
         self.port.borrow().is_textual()
     }
 
+    pub fn is_seekable(&self) -> bool {
+        self.port.borrow().is_seekable()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.port.borrow().is_closed()
+    }
+
     pub fn default_current_input_port() -> Self {
         SteelPort {
             port: new_rc_ref_cell(SteelPortRepr::StdInput(io::stdin())),
@@ -447,7 +549,11 @@ impl SteelPort {
     }
 
     pub fn get_output_string(&self) -> Result<String> {
-        self.port.borrow_mut().get_output_string()
+        self.open("get the output string of")?.get_output_string()
+    }
+
+    pub fn close(&self) {
+        self.port.borrow_mut().close()
     }
 
     pub fn close_output_port(&self) -> Result<()> {
@@ -58,6 +58,9 @@ define_symbols! {
     UNSYNTAX_SPLICING => "unsyntax-splicing",
     RAW_UNSYNTAX_SPLICING => "#%unsyntax-splicing",
     SYNTAX_QUOTE => "syntax",
+    VECTOR => "vector",
+    BYTEVECTOR => "bytevector",
+    DOT => ".",
 }
 
 pub trait AstTools {
@@ -417,6 +420,8 @@ impl ExprKind {
     }
 }
 
+/// Prints the expression back out as Scheme source, such that parsing the output
+/// yields an equal [`ExprKind`].
 impl fmt::Display for ExprKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -746,6 +751,10 @@ impl PartialEq for LambdaFunction {
 
 impl fmt::Display for LambdaFunction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.rest && self.args.len() == 1 {
+            return write!(f, "(lambda {} {})", self.args[0], self.body);
+        }
+
         write!(
             f,
             "(lambda ({}) {})",
@@ -946,11 +955,22 @@ impl From<Require> for ExprKind {
     }
 }
 
+/// What a `#(...)` or `#u8(...)` literal in quoted data reads as, see [`List::vector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VectorKind {
+    Vector,
+    Bytevector,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct List {
     pub args: Vec<ExprKind>,
     pub syntax_object_id: usize,
     pub improper: bool,
+    /// Set for the elements of a vector or bytevector literal in quoted data, which is a
+    /// vector datum rather than a list. Outside of quoted data, `#(a b)` is read as
+    /// `(vector 'a 'b)`, and `#u8(1 2)` as `(bytevector '1 '2)`.
+    pub vector: Option<VectorKind>,
     // TODO: Attach the span from the parser - just the offset
     // of the open and close parens
     pub location: Option<Span>,
@@ -958,7 +978,7 @@ pub struct List {
 
 impl PartialEq for List {
     fn eq(&self, other: &Self) -> bool {
-        self.args == other.args
+        self.args == other.args && self.improper == other.improper && self.vector == other.vector
     }
 }
 
@@ -968,6 +988,7 @@ impl List {
             args,
             syntax_object_id: SyntaxObjectId::fresh().0,
             improper: false,
+            vector: None,
             location: None,
         }
     }
@@ -982,6 +1003,11 @@ impl List {
         self
     }
 
+    pub fn make_vector(mut self, kind: VectorKind) -> Self {
+        self.vector = Some(kind);
+        self
+    }
+
     pub fn is_empty(&self) -> bool {
         self.args.is_empty()
     }
@@ -1166,7 +1192,18 @@ impl ToDoc for List {
 
 impl fmt::Display for List {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({})", self.args.iter().join(" "))
+        match self.vector {
+            Some(VectorKind::Vector) => write!(f, "#")?,
+            Some(VectorKind::Bytevector) => write!(f, "#u8")?,
+            None => {}
+        }
+
+        match self.args.split_last() {
+            Some((tail, init)) if self.improper => {
+                write!(f, "({} . {})", init.iter().join(" "), tail)
+            }
+            _ => write!(f, "({})", self.args.iter().join(" ")),
+        }
     }
 }
 
@@ -1208,17 +1245,13 @@ impl Quote {
 
 impl ToDoc for Quote {
     fn to_doc(&self) -> RcDoc<()> {
-        RcDoc::text("(quote")
-            .append(RcDoc::line())
-            .append(self.expr.to_doc())
-            .append(RcDoc::text(")"))
-            .nest(2)
+        RcDoc::text("'").append(self.expr.to_doc())
     }
 }
 
 impl fmt::Display for Quote {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "(quote {})", self.expr)
+        write!(f, "'{}", self.expr)
     }
 }
 
@@ -1505,10 +1538,11 @@ where
                 ))
             };
 
-            let lambda = ExprKind::LambdaFunction(Box::new(LambdaFunction::new(
+            let lambda = ExprKind::LambdaFunction(Box::new(LambdaFunction::new_maybe_rest(
                 args,
                 body,
                 SyntaxObject::new(TokenType::Lambda, syn.span),
+                l.improper,
             )));
 
             Ok(ExprKind::Define(Box::new(Define::new(name, lambda, syn))))
@@ -2033,6 +2067,7 @@ pub fn parse_lambda(a: Atom, value: Vec<ExprKind>) -> Result<ExprKind, ParseErro
     let arguments = value_iter.next();
     match arguments {
         Some(ExprKind::List(l)) => {
+            let rest = l.improper;
             let args = l.args;
 
             for arg in &args {
@@ -2061,9 +2096,9 @@ pub fn parse_lambda(a: Atom, value: Vec<ExprKind>) -> Result<ExprKind, ParseErro
                 ))
             };
 
-            Ok(ExprKind::LambdaFunction(Box::new(LambdaFunction::new(
-                args, body, syn,
-            ))))
+            Ok(ExprKind::LambdaFunction(Box::new(
+                LambdaFunction::new_maybe_rest(args, body, syn, rest),
+            )))
         }
         Some(ExprKind::Atom(a)) => {
             let body_exprs: Vec<_> = value_iter.collect();
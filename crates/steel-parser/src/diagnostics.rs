@@ -0,0 +1,169 @@
+//! Lexer errors rendered for a terminal, pointing at the offending source like `rustc` does.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::lexer::{lex, Lexer, TokenError};
+use crate::span::Span;
+use crate::tokens::Token;
+
+/// A [`TokenError`] along with where it happened in the input, and a rendering of it that
+/// quotes the source line with a `^` under each offending character:
+///
+/// ```text
+/// error: unknown # syntax: #$
+///  --> 1:9
+///   |
+/// 1 | (define #$ x)
+///   |         ^^
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub error: TokenError,
+    pub span: Span,
+    /// The line and column the error starts at, both starting at 1.
+    pub line: usize,
+    pub column: usize,
+    rendered: String,
+}
+
+impl Diagnostic {
+    /// Describes `error` at `span` of `input`.
+    pub fn new(input: &str, span: Span, error: TokenError) -> Self {
+        let (line, column) = Lexer::new(input).line_column(span.start);
+
+        let line_start = input[..span.start]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+        let line_end = input[span.start..]
+            .find('\n')
+            .map_or(input.len(), |newline| span.start + newline);
+        let text = input[line_start..line_end].trim_end_matches('\r');
+
+        // Tabs are copied, so that the carets line up however wide the terminal draws them
+        let indent: String = input[line_start..span.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        // Errors running past the end of the line, like an unclosed string, are only
+        // underlined up to it
+        let width = input[span.start..span.end.min(line_end)]
+            .chars()
+            .count()
+            .max(1);
+
+        let gutter = " ".repeat(line.to_string().len());
+        let rendered = format!(
+            "error: {}\n{gutter}--> {line}:{column}\n{gutter} |\n{line} | {text}\n{gutter} | {indent}{}",
+            message(&error),
+            "^".repeat(width),
+        );
+
+        Diagnostic {
+            error,
+            span,
+            line,
+            column,
+            rendered,
+        }
+    }
+
+    pub fn rendered(&self) -> &str {
+        &self.rendered
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.rendered)
+    }
+}
+
+fn message(error: &TokenError) -> String {
+    match error {
+        TokenError::UnexpectedChar(c) => format!("unexpected character {c:?}"),
+        TokenError::ControlCharacter(c) => format!("stray control character {c:?}"),
+        TokenError::IncompleteString { .. } => "unterminated string".to_string(),
+        TokenError::InvalidEscape => "invalid escape sequence".to_string(),
+        TokenError::IncompleteIdentifier => "unterminated |identifier|".to_string(),
+        TokenError::InvalidCharacter => "invalid character literal".to_string(),
+        TokenError::MalformedHexInteger => "malformed hexadecimal integer".to_string(),
+        TokenError::MalformedOctalInteger => "malformed octal integer".to_string(),
+        TokenError::MalformedBinaryInteger => "malformed binary integer".to_string(),
+        TokenError::MalformedByteEscape => "malformed \\x byte escape".to_string(),
+        TokenError::InvalidNumber => "invalid number".to_string(),
+        TokenError::UnbalancedClose => "unbalanced closing delimiter".to_string(),
+        TokenError::IncompleteForm => "unclosed delimiter".to_string(),
+        TokenError::InvalidHashSyntax(syntax) => format!("unknown # syntax: {syntax}"),
+        TokenError::NumberTooLong => "number has too many digits".to_string(),
+        TokenError::TokenTooLong => "token is too long".to_string(),
+        TokenError::NestingTooDeep => "nesting is too deep".to_string(),
+    }
+}
+
+/// Lexes all of `input`, skipping comments. Lexing carries on after errors like with
+/// [`lex`], so that all of them are reported at once.
+pub fn tokenize_with_diagnostics(input: &str) -> Result<Vec<Token<'_, &str>>, Vec<Diagnostic>> {
+    let lexed = lex(input);
+
+    if lexed.errors.is_empty() {
+        Ok(lexed.tokens)
+    } else {
+        Err(lexed
+            .errors
+            .into_iter()
+            .map(|(span, error)| Diagnostic::new(input, span, error))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::*;
+
+    #[test]
+    fn test_caret_under_error() {
+        let Err(diagnostics) = tokenize_with_diagnostics("(define a 1)\n(define #$ x)\n(b)") else {
+            panic!("expected an error");
+        };
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].error,
+            TokenError::InvalidHashSyntax("#$".to_string())
+        );
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 9));
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error: unknown # syntax: #$\n --> 2:9\n  |\n2 | (define #$ x)\n  |         ^^"
+        );
+    }
+
+    #[test]
+    fn test_every_error_is_reported() {
+        let input = "\t(a #$ #\\bogus)";
+        let Err(diagnostics) = tokenize_with_diagnostics(input) else {
+            panic!("expected errors");
+        };
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.rendered().lines().last().unwrap())
+                .collect::<Vec<_>>(),
+            ["  | \t   ^^", "  | \t      ^^^^^^^"]
+        );
+    }
+
+    #[test]
+    fn test_no_errors() {
+        let tokens = tokenize_with_diagnostics("(a b)").unwrap();
+
+        assert_eq!(
+            tokens.iter().map(|token| token.source).collect::<Vec<_>>(),
+            ["(", "a", "b", ")"]
+        );
+    }
+}
@@ -3,7 +3,9 @@ use lasso::Key;
 use lasso::Spur;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use std::{fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use crate::lexer::ToOwnedString;
 
 // TODO: Serialize and Deserialize should resolve() -> Otherwise we're in for deep trouble
 // trying to serialize and deserialize this
@@ -152,3 +154,96 @@ fn test_initialization() {
 fn resolve(key: &Spur) -> &str {
     INTERNER.get().unwrap().resolve(key)
 }
+
+/// An identifier interned in an [`Interner`]. Comparing two symbols from the same interner
+/// is comparing their names.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// Maps identifiers to [`Symbol`]s, allocating every distinct name only once. Unlike
+/// [`InternedString`] this isn't global, so the symbols are only meaningful together with
+/// the interner that made them.
+///
+/// Pass it to [`TokenStream::into_owned`](crate::lexer::TokenStream::into_owned) to lex
+/// identifiers and keywords straight into symbols:
+///
+/// ```
+/// # use steel_parser::{interner::Interner, lexer::TokenStream, tokens::TokenType};
+/// let mut interner = Interner::new();
+/// let tokens: Vec<_> = TokenStream::new("(f x x)", true, None)
+///     .into_owned(&mut interner)
+///     .map(|token| token.ty)
+///     .collect();
+///
+/// assert_eq!(tokens[2], tokens[3]);
+/// let TokenType::Identifier(x) = tokens[2] else { unreachable!() };
+/// assert_eq!(interner.resolve(x), "x");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    symbols: HashMap<String, Symbol>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The symbol for `name`, allocating it if it hasn't been seen before.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(name.to_string());
+        self.symbols.insert(name.to_string(), symbol);
+        symbol
+    }
+
+    /// The symbol for `name`, if it has been interned.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    /// The name of `symbol`. Panics if it was made by a different interner.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.0 as usize]
+    }
+
+    /// The number of distinct names interned so far.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl ToOwnedString<Symbol> for &mut Interner {
+    fn own(&mut self, s: &str) -> Symbol {
+        self.intern(s)
+    }
+}
+
+#[test]
+fn test_local_interner() {
+    let mut interner = Interner::new();
+
+    let define = interner.intern("define");
+    let x = interner.intern("x");
+
+    assert_eq!(interner.intern("define"), define);
+    assert_ne!(define, x);
+    assert_eq!(interner.resolve(x), "x");
+    assert_eq!(interner.get("y"), None);
+    assert_eq!(interner.len(), 2);
+}
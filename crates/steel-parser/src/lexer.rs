@@ -1,30 +1,69 @@
-use super::parser::SourceId;
+use crate::span::SourceId;
 use crate::tokens::{parse_unicode_str, NumberLiteral, RealLiteral};
-use crate::tokens::{IntLiteral, Token, TokenType};
+use crate::tokens::{CommentLevel, IntLiteral, Token, TokenCategory, TokenType};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Iterator;
+use core::marker::PhantomData;
+use core::num::IntErrorKind;
+use core::{iter::Peekable, ops::Range, str::Chars};
+use num::{BigInt, BigRational, One, ToPrimitive, Zero};
 use smallvec::SmallVec;
-use std::iter::Iterator;
-use std::marker::PhantomData;
-use std::{iter::Peekable, str::Chars};
 
 pub const INFINITY: &str = "+inf.0";
 pub const NEG_INFINITY: &str = "-inf.0";
 pub const NAN: &str = "+nan.0";
 pub const NEG_NAN: &str = "-nan.0";
 
+/// Plenty for any bignum written by hand, while keeping untrusted input from making the
+/// lexer parse arbitrarily large numbers.
+pub const DEFAULT_MAX_NUMBER_DIGITS: usize = 10_000;
+
 pub struct OwnedString;
 
 impl ToOwnedString<String> for OwnedString {
-    fn own(&self, s: &str) -> String {
+    fn own(&mut self, s: &str) -> String {
         s.to_string()
     }
 }
 
+/// Turns the text of identifiers and keywords into the owned type stored in tokens, see
+/// [`TokenStream::into_owned`].
 pub trait ToOwnedString<T> {
-    fn own(&self, s: &str) -> T;
+    fn own(&mut self, s: &str) -> T;
 }
 
 pub type Span = core::ops::Range<usize>;
 
+/// How columns are counted by [`Lexer::line_column`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// Unicode scalar values, i.e. `char`s.
+    #[default]
+    Chars,
+    /// Extended grapheme clusters, which is what a user perceives as a single character,
+    /// e.g. an `e` followed by a combining accent.
+    #[cfg(feature = "graphemes")]
+    Graphemes,
+}
+
+impl ColumnUnit {
+    fn count(self, s: &str) -> usize {
+        match self {
+            ColumnUnit::Chars => s.chars().count(),
+            #[cfg(feature = "graphemes")]
+            ColumnUnit::Graphemes => {
+                unicode_segmentation::UnicodeSegmentation::graphemes(s, true).count()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Lexer<'a> {
     /// The source of the lexer.
     source: &'a str,
@@ -34,19 +73,299 @@ pub struct Lexer<'a> {
     queued: Option<TokenType<&'a str>>,
     token_start: usize,
     token_end: usize,
+    /// Whether `{` and `}` are lexed as SRFI-105 curly-infix delimiters,
+    /// rather than as ordinary parentheses.
+    pub(crate) curly_infix: bool,
+    /// The number of currently unclosed delimiters.
+    depth: usize,
+    /// Whether identifiers are case-insensitive, toggled by the `#!fold-case` and
+    /// `#!no-fold-case` directives.
+    fold_case: bool,
+    /// Whether `...` is lexed as an ordinary identifier rather than as
+    /// [`TokenType::Ellipses`], like the other peculiar identifiers `+` and `-`.
+    pub(crate) peculiar_identifiers: bool,
+    /// The words that are lexed as keyword tokens, or `None` to use all of them.
+    keywords: Option<BTreeSet<String>>,
+    column_unit: ColumnUnit,
+    /// Tabs advance the column to the next multiple of this in [`Lexer::line_column`].
+    tab_width: usize,
+    /// Whether [`Lexer::line_column`] counts lines and columns from 0 rather than 1.
+    zero_based: bool,
+    /// The longest number literal, in bytes, that will be parsed.
+    max_number_digits: usize,
+    /// The longest token, in bytes, or `None` for no limit.
+    max_token_len: Option<usize>,
+    /// The deepest nesting of delimiters, or `None` for no limit.
+    max_depth: Option<usize>,
+    /// Whether runs of whitespace are returned as [`TokenType::Whitespace`] tokens.
+    trivia: bool,
+    /// Whether `,` is lexed as [`TokenType::Comma`] rather than as unquote.
+    comma_separators: bool,
+    /// The character starting line comments, or `None` if there are none.
+    comment_char: Option<char>,
+    /// Whether `=>` is lexed as [`TokenType::Arrow`] rather than as an identifier.
+    arrows: bool,
+    /// Whether `#t` and `#f` are lexed like `#true` and `#false`, rather than as
+    /// [`TokenType::ShortBooleanLiteral`].
+    normalize_booleans: bool,
+    /// Whether integers can be written with C-style `0x`, `0o` and `0b` radix prefixes.
+    c_radix_prefixes: bool,
+    /// Whether an unterminated string is returned as [`TokenType::PartialString`] rather
+    /// than as an error.
+    partial_strings: bool,
+    /// Errors that were recovered from rather than returned, see [`Lexer::diagnostics`].
+    diagnostics: Vec<(crate::span::Span, TokenError)>,
+}
+
+// The byte order mark that some editors save UTF-8 files with.
+const BOM: char = '\u{FEFF}';
+
+// The length of the byte order mark at the start of `source`, which is skipped.
+fn bom_len(source: &str) -> usize {
+    if source.starts_with(BOM) {
+        BOM.len_utf8()
+    } else {
+        0
+    }
 }
 
 impl<'a> Lexer<'a> {
+    /// Lexes `source`, skipping a leading byte order mark. Spans still count its bytes, so
+    /// that they are offsets into `source`.
     pub fn new(source: &'a str) -> Self {
+        let start = bom_len(source);
+
         Self {
             source,
-            chars: source.chars().peekable(),
+            chars: source[start..].chars().peekable(),
             queued: None,
-            token_start: 0,
-            token_end: 0,
+            token_start: start,
+            token_end: start,
+            curly_infix: false,
+            depth: 0,
+            fold_case: false,
+            peculiar_identifiers: false,
+            keywords: None,
+            column_unit: ColumnUnit::Chars,
+            tab_width: 1,
+            zero_based: false,
+            max_number_digits: DEFAULT_MAX_NUMBER_DIGITS,
+            max_token_len: None,
+            max_depth: None,
+            trivia: false,
+            comma_separators: false,
+            comment_char: Some(';'),
+            arrows: false,
+            normalize_booleans: true,
+            c_radix_prefixes: false,
+            partial_strings: false,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Reject number literals longer than `max` with [`TokenError::NumberTooLong`], rather
+    /// than parsing them. Defaults to [`DEFAULT_MAX_NUMBER_DIGITS`].
+    pub fn with_max_number_digits(mut self, max: usize) -> Self {
+        self.max_number_digits = max;
+        self
+    }
+
+    /// Reject tokens longer than `max` bytes with [`TokenError::TokenTooLong`]. String
+    /// literals stop being read as soon as they exceed the limit. Comments and whitespace
+    /// can be any length.
+    pub fn with_max_token_len(mut self, max: usize) -> Self {
+        self.max_token_len = Some(max);
+        self
+    }
+
+    /// Reject opening delimiters nested more than `max` deep with
+    /// [`TokenError::NestingTooDeep`].
+    pub fn with_max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+
+    /// Return whitespace as [`TokenType::Whitespace`] tokens rather than skipping it, so
+    /// that the slices of all tokens, comments included, add up to the whole input.
+    /// Meant for formatters, which need to reproduce the source around the code.
+    pub fn with_trivia(mut self) -> Self {
+        self.trivia = true;
+        self
+    }
+
+    /// Lex `,` as a [`TokenType::Comma`] separator, for data like `(a, b)`. This turns
+    /// off `,` and `,@` as unquote, so quasiquote can't be used in this dialect.
+    pub fn with_comma_separators(mut self) -> Self {
+        self.comma_separators = true;
+        self
+    }
+
+    /// Start line comments with `comment_char` rather than `;`, for dialects like config
+    /// files that use `#`, or have no line comments at all with `None`. Any other use of the
+    /// character, like `#t` for `#`, is read as a comment too. Once `;` doesn't start
+    /// comments, it is an ordinary character that can be part of identifiers.
+    pub fn with_comment_char(mut self, comment_char: Option<char>) -> Self {
+        self.comment_char = comment_char;
+        self
+    }
+
+    /// Lex `=>` as a [`TokenType::Arrow`], for parsers that want to tell `(test => proc)`
+    /// clauses in `cond` and `case` apart without looking at identifiers. Off by default,
+    /// because Steel's own `cond` is a macro that matches `=>` as a literal identifier.
+    pub fn with_arrows(mut self) -> Self {
+        self.arrows = true;
+        self
+    }
+
+    /// Whether `#t` and `#f` are read as the same [`TokenType::BooleanLiteral`] as `#true`
+    /// and `#false`, which is the default. Formatters can turn this off to get them as a
+    /// [`TokenType::ShortBooleanLiteral`] instead, which displays with the spelling it was
+    /// written with.
+    pub fn with_normalize_booleans(mut self, normalize: bool) -> Self {
+        self.normalize_booleans = normalize;
+        self
+    }
+
+    /// Also read integers written with C-style radix prefixes, like `0xFF`, `0o17` and
+    /// `0b101`, next to Scheme's `#xFF`. Unlike in C, a leading `0` on its own doesn't
+    /// make a number octal: `012` is still twelve, like it is in Scheme.
+    pub fn with_c_radix_prefixes(mut self) -> Self {
+        self.c_radix_prefixes = true;
+        self
+    }
+
+    /// Return a string cut off by the end of the input as a [`TokenType::PartialString`]
+    /// holding what was typed so far, rather than as [`TokenError::IncompleteString`], so that
+    /// an editor can keep highlighting a string while it is being typed. The error is still
+    /// recorded in [`Lexer::diagnostics`].
+    pub fn with_partial_strings(mut self) -> Self {
+        self.partial_strings = true;
+        self
+    }
+
+    /// Count columns in `unit`s in [`Lexer::line_column`].
+    pub fn with_column_unit(mut self, unit: ColumnUnit) -> Self {
+        self.column_unit = unit;
+        self
+    }
+
+    /// Make a tab advance the column in [`Lexer::line_column`] to the next multiple of
+    /// `width`, like editors with tab stops do. Defaults to 1, so that a tab is a single
+    /// column like any other character.
+    pub fn with_tab_width(mut self, width: usize) -> Self {
+        self.tab_width = width.max(1);
+        self
+    }
+
+    /// Count lines and columns from 0 in [`Lexer::line_column`], like the Language Server
+    /// Protocol does, rather than from 1 like error messages for humans do.
+    pub fn with_zero_based_positions(mut self) -> Self {
+        self.zero_based = true;
+        self
+    }
+
+    /// The line and column of the byte `offset` into the source, both starting at 1, or
+    /// at 0 when built [`with_zero_based_positions`](Lexer::with_zero_based_positions).
+    /// Lines can end with `\n`, `\r\n` or just `\r`.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let before = &self.source[..offset];
+
+        let mut line = 1;
+        let mut line_start = 0;
+
+        for (idx, c) in before.char_indices() {
+            let is_line_end = c == '\n' || (c == '\r' && !self.source[idx + 1..].starts_with('\n'));
+
+            if is_line_end {
+                line += 1;
+                line_start = idx + 1;
+            }
+        }
+
+        // A leading byte order mark doesn't take up a column
+        let line_start = line_start.max(bom_len(self.source)).min(offset);
+
+        let mut column = 0;
+
+        for (idx, segment) in before[line_start..].split('\t').enumerate() {
+            if idx > 0 {
+                column = (column / self.tab_width + 1) * self.tab_width;
+            }
+
+            column += self.column_unit.count(segment);
+        }
+
+        if self.zero_based {
+            (line - 1, column)
+        } else {
+            (line, column + 1)
         }
     }
 
+    /// Starts lexing `source` from the beginning, forgetting everything about the previous
+    /// input, including a `#!fold-case` directive. Options set with the builder methods
+    /// are kept.
+    pub fn reset(&mut self, source: &'a str) {
+        let start = bom_len(source);
+
+        self.source = source;
+        self.chars = source[start..].chars().peekable();
+        self.queued = None;
+        self.token_start = start;
+        self.token_end = start;
+        self.depth = 0;
+        self.fold_case = false;
+        self.diagnostics.clear();
+    }
+
+    /// The part of the input that hasn't been read yet, for handing the rest of the source
+    /// over to a different reader.
+    pub fn remaining(&self) -> &'a str {
+        &self.source[self.token_end..]
+    }
+
+    /// The number of delimiters opened so far that haven't been closed yet. Extra
+    /// closing delimiters don't make this go below zero, use [`check_balanced`] to
+    /// detect those.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The errors the lexer recovered from so far, together with the span of the token
+    /// they were found in, e.g. the strings returned as [`TokenType::PartialString`].
+    pub fn diagnostics(&self) -> &[(crate::span::Span, TokenError)] {
+        &self.diagnostics
+    }
+
+    /// Whether a `#!fold-case` directive is in effect. Identifiers borrow from the source,
+    /// so they are folded to lowercase once they get owned, see [`OwnedTokenStream`].
+    pub fn fold_case(&self) -> bool {
+        self.fold_case
+    }
+
+    /// Emit [`TokenType::OpenCurly`] and [`TokenType::CloseCurly`] for `{` and `}`, so that
+    /// the parser can apply the curly-infix transformation, i.e. `{a + b}` => `(+ a b)`.
+    pub fn with_curly_infix(mut self) -> Self {
+        self.curly_infix = true;
+        self
+    }
+
+    /// Lex `...` as [`TokenType::Identifier`], so that it can be bound and referenced like
+    /// `+` and `-`, which are always identifiers when they don't start a number.
+    pub fn with_peculiar_identifiers(mut self) -> Self {
+        self.peculiar_identifiers = true;
+        self
+    }
+
+    /// Only lex the given words as keyword tokens like [`TokenType::Define`], and every other
+    /// word as an [`TokenType::Identifier`]. Aliases are separate words, i.e. `fn` only
+    /// becomes [`TokenType::Lambda`] if it is in the set. Words without a keyword token of
+    /// their own are always identifiers.
+    pub fn with_keywords(mut self, keywords: &BTreeSet<&str>) -> Self {
+        self.keywords = Some(keywords.iter().map(|keyword| keyword.to_string()).collect());
+        self
+    }
+
     fn eat(&mut self) -> Option<char> {
         if let Some(c) = self.chars.next() {
             self.token_end += c.len_utf8();
@@ -75,6 +394,11 @@ impl<'a> Lexer<'a> {
 
         let mut buf = String::new();
         while let Some(&c) = self.chars.peek() {
+            if self.exceeds_max_token_len() {
+                self.skip_rest_of_string();
+                return Err(TokenError::TokenTooLong);
+            }
+
             self.eat();
             match c {
                 '"' => return Ok(TokenType::StringLiteral(buf)),
@@ -112,6 +436,29 @@ impl<'a> Lexer<'a> {
                     Some('x') => {
                         self.eat();
 
+                        // R7RS `\x<hex>;` names any code point, `\x<hex><hex>` without
+                        // the `;` is a single byte
+                        let rest = &self.source[self.token_end..];
+                        let digits = rest.bytes().take_while(u8::is_ascii_hexdigit).count();
+
+                        if digits > 0 && rest[digits..].starts_with(';') {
+                            // Surrogates and values past `char::MAX` aren't characters
+                            let Some(char) = u32::from_str_radix(&rest[..digits], 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                            else {
+                                self.skip_rest_of_string();
+                                return Err(TokenError::InvalidEscape);
+                            };
+
+                            for _ in 0..=digits {
+                                self.eat();
+                            }
+
+                            buf.push(char);
+                            continue;
+                        }
+
                         let digit1 = self.eat().ok_or_else(|| TokenError::MalformedByteEscape)?;
                         let digit2 = self.eat().ok_or_else(|| TokenError::MalformedByteEscape)?;
 
@@ -128,19 +475,27 @@ impl<'a> Lexer<'a> {
                         buf.push(char);
                     }
 
+                    None => {
+                        return Err(TokenError::IncompleteString {
+                            partial: buf,
+                            in_escape: true,
+                        })
+                    }
                     _ => return Err(TokenError::InvalidEscape),
                 },
                 _ => buf.push(c),
             }
         }
 
-        buf.insert(0, '"');
-        Err(TokenError::IncompleteString)
+        Err(TokenError::IncompleteString {
+            partial: buf,
+            in_escape: false,
+        })
     }
 
     fn read_hash_value(&mut self) -> Result<TokenType<&'a str>> {
         fn parse_char(slice: &str) -> Option<char> {
-            use std::str::FromStr;
+            use core::str::FromStr;
 
             match slice {
                 "#\\SPACE" => Some(' '),
@@ -170,14 +525,53 @@ impl<'a> Lexer<'a> {
             }
         }
 
+        if let Some('(') = self.chars.peek() {
+            self.eat();
+            return Ok(TokenType::OpenVector);
+        }
+
+        if let Some(';') = self.chars.peek() {
+            self.eat();
+            return Ok(TokenType::DatumComment);
+        }
+
+        // The syntax reader prefixes, which are delimiters themselves
+        match self.chars.peek() {
+            Some('\'') => {
+                self.eat();
+                return Ok(TokenType::QuoteSyntax);
+            }
+            Some('`') => {
+                self.eat();
+                return Ok(TokenType::QuasiQuoteSyntax);
+            }
+            Some(',') => {
+                self.eat();
+
+                if let Some('@') = self.chars.peek() {
+                    self.eat();
+                    return Ok(TokenType::UnquoteSpliceSyntax);
+                }
+
+                return Ok(TokenType::UnquoteSyntax);
+            }
+            _ => {}
+        }
+
+        if self.source[self.token_end..].starts_with("u8(") {
+            self.eat();
+            self.eat();
+            self.eat();
+            return Ok(TokenType::OpenBytevector);
+        }
+
         while let Some(&c) = self.chars.peek() {
             match c {
                 '\\' => {
                     self.eat();
                     self.eat();
                 }
-                '(' | '[' | ')' | ']' => break,
-                c if c.is_whitespace() => break,
+                c if self.is_delimiter(c) => break,
                 _ => {
                     self.eat();
                 }
@@ -185,13 +579,14 @@ impl<'a> Lexer<'a> {
         }
 
         match self.slice() {
+            "#t" if !self.normalize_booleans => Ok(TokenType::ShortBooleanLiteral(true)),
+            "#f" if !self.normalize_booleans => Ok(TokenType::ShortBooleanLiteral(false)),
             "#true" | "#t" => Ok(TokenType::BooleanLiteral(true)),
             "#false" | "#f" => Ok(TokenType::BooleanLiteral(false)),
 
-            "#'" => Ok(TokenType::QuoteSyntax),
-            "#`" => Ok(TokenType::QuasiQuoteSyntax),
-            "#," => Ok(TokenType::UnquoteSyntax),
-            "#,@" => Ok(TokenType::UnquoteSpliceSyntax),
+            number if matches!(split_number_prefixes(number), Some((Some(_), _, _))) => {
+                self.read_prefixed_number(number)
+            }
 
             hex if hex.starts_with("#x") => {
                 let hex = isize::from_str_radix(hex.strip_prefix("#x").unwrap(), 16)
@@ -216,6 +611,9 @@ impl<'a> Lexer<'a> {
 
             keyword if keyword.starts_with("#:") => Ok(TokenType::Keyword(self.slice())),
 
+            // A bytevector prefix that isn't followed by its opening paren
+            malformed @ ("#u" | "#u8") => Err(TokenError::InvalidHashSyntax(malformed.to_string())),
+
             character if character.starts_with("#\\") => {
                 if let Some(parsed_character) = parse_char(character) {
                     Ok(TokenType::CharacterLiteral(parsed_character))
@@ -224,11 +622,83 @@ impl<'a> Lexer<'a> {
                 }
             }
 
-            _ => Ok(self.read_word()),
+            // Identifiers introduced by the expander, like `#%define` or `##lambda-lifting##`
+            identifier if identifier.starts_with("#%") || identifier.starts_with("##") => {
+                Ok(self.read_word())
+            }
+
+            other => Err(TokenError::InvalidHashSyntax(other.to_string())),
+        }
+    }
+
+    // Consumes a `#!fold-case` or `#!no-fold-case` directive following the `#`, and returns
+    // whether case folding should be enabled from here on.
+    fn read_fold_case_directive(&mut self) -> Option<bool> {
+        let rest = &self.source[self.token_end..];
+
+        let (directive, fold_case) = [("!fold-case", true), ("!no-fold-case", false)]
+            .into_iter()
+            .find(|(directive, _)| {
+                rest.strip_prefix(*directive).map_or(false, |after| {
+                    after.chars().next().map_or(true, |c| self.is_delimiter(c))
+                })
+            })?;
+
+        for _ in directive.chars() {
+            self.eat();
         }
+
+        Some(fold_case)
+    }
+
+    fn read_prefixed_number(&self, slice: &str) -> Result<TokenType<&'a str>> {
+        let (exactness, radix, digits) =
+            split_number_prefixes(slice).ok_or(TokenError::InvalidNumber)?;
+
+        if digits.len() > self.max_number_digits {
+            return Err(TokenError::NumberTooLong);
+        }
+
+        let number = match radix {
+            10 => parse_number(digits).ok_or(TokenError::InvalidNumber)?,
+            _ => {
+                let value = isize::from_str_radix(digits, radix).map_err(|_| match radix {
+                    16 => TokenError::MalformedHexInteger,
+                    8 => TokenError::MalformedOctalInteger,
+                    _ => TokenError::MalformedBinaryInteger,
+                })?;
+
+                RealLiteral::Int(IntLiteral::Small(value)).into()
+            }
+        };
+
+        let number = match exactness {
+            Some(true) => into_exact(number, digits, self.max_number_digits)?,
+            Some(false) => into_inexact(number),
+            None => number,
+        };
+
+        Ok(number.into())
+    }
+
+    // Whether the input continues with a number like `.5`, as opposed to a lone `.` or
+    // an identifier like `...`.
+    fn at_leading_dot_number(&self) -> bool {
+        let mut rest = self.source[self.token_end..].chars();
+
+        rest.next() == Some('.') && rest.next().map_or(false, |c| c.is_numeric())
     }
 
-    fn read_number(&mut self) -> TokenType<&'a str> {
+    fn read_number(&mut self) -> Result<TokenType<&'a str>> {
+        if self.c_radix_prefixes {
+            match self.source[self.token_end..].get(..2) {
+                Some("0x" | "0X") => return self.read_prefixed_integer(16),
+                Some("0o" | "0O") => return self.read_prefixed_integer(8),
+                Some("0b" | "0B") => return self.read_prefixed_integer(2),
+                _ => {}
+            }
+        }
+
         while let Some(&c) = self.chars.peek() {
             match c {
                 c if c.is_numeric() => {
@@ -237,45 +707,115 @@ impl<'a> Lexer<'a> {
                 '+' | '-' | '.' | '/' | 'e' | 'E' | 'i' => {
                     self.eat();
                 }
-                '(' | ')' | '[' | ']' => {
-                    return if let Some(t) = parse_number(self.slice()) {
-                        t.into()
-                    } else {
-                        self.read_word()
-                    }
-                }
-                c if c.is_whitespace() => {
-                    return if let Some(t) = parse_number(self.slice()) {
-                        t.into()
-                    } else {
-                        self.read_word()
-                    }
-                }
-                _ => return self.read_word(),
+                c if self.is_delimiter(c) => return self.finish_number(),
+                _ => return Ok(self.read_word()),
             }
+
+            // Bail out before parsing, which is where huge literals get expensive
+            if self.token_end - self.token_start > self.max_number_digits {
+                self.skip_rest_of_token();
+                return Err(TokenError::NumberTooLong);
+            }
+        }
+        self.finish_number()
+    }
+
+    // Reads a C-style integer like `0xFF`, after any sign, failing like the `#x` syntax
+    // if the digits don't fit the radix.
+    fn read_prefixed_integer(&mut self, radix: u32) -> Result<TokenType<&'a str>> {
+        self.skip_rest_of_token();
+
+        let error = match radix {
+            16 => TokenError::MalformedHexInteger,
+            8 => TokenError::MalformedOctalInteger,
+            _ => TokenError::MalformedBinaryInteger,
+        };
+
+        let literal = self.slice();
+        let (negative, unsigned) = match literal.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, literal.strip_prefix('+').unwrap_or(literal)),
+        };
+        let digits = &unsigned[2..];
+
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(error);
         }
+
+        let value = isize::from_str_radix(digits, radix).map_err(|_| error)?;
+
+        Ok(IntLiteral::Small(if negative { -value } else { value }).into())
+    }
+
+    // Parses the number read so far, falling back to an identifier like `1+` if it isn't
+    // one. A rational like `1/0` is rejected rather than read as an identifier.
+    fn finish_number(&mut self) -> Result<TokenType<&'a str>> {
         match parse_number(self.slice()) {
-            Some(n) => n.into(),
-            None => self.read_word(),
+            Some(n) => Ok(n.into()),
+            None if has_zero_denominator(self.slice()) => Err(TokenError::InvalidNumber),
+            None => Ok(self.read_word()),
         }
     }
 
-    fn read_rest_of_line(&mut self) {
+    fn exceeds_max_token_len(&self) -> bool {
+        self.max_token_len
+            .map_or(false, |max| self.token_end - self.token_start > max)
+    }
+
+    // Consumes the rest of a string literal without keeping its contents.
+    fn skip_rest_of_string(&mut self) {
         while let Some(c) = self.eat() {
-            if c == '\n' {
-                break;
+            match c {
+                '"' => break,
+                '\\' => {
+                    self.eat();
+                }
+                _ => {}
             }
         }
     }
 
-    fn read_word(&mut self) -> TokenType<&'a str> {
+    // Consumes everything up to the next delimiter, so that lexing can continue after a
+    // token that was rejected.
+    fn skip_rest_of_token(&mut self) {
         while let Some(&c) = self.chars.peek() {
+            if self.is_delimiter(c) {
+                break;
+            }
+
+            self.eat();
+        }
+    }
+
+    /// Whether `c` ends the word, number or `#` syntax before it: one of the shared
+    /// [`is_delimiter`] characters, or the character starting a comment.
+    fn is_delimiter(&self, c: char) -> bool {
+        is_delimiter(c) || self.comment_char == Some(c)
+    }
+
+    fn read_rest_of_line(&mut self) {
+        while let Some(c) = self.eat() {
             match c {
-                '(' | '[' | ')' | ']' => break,
-                c if c.is_whitespace() => break,
-                '\'' => {
+                '\n' => break,
+                '\r' => {
+                    if let Some('\n') = self.chars.peek() {
+                        self.eat();
+                    }
+
                     break;
                 }
+                _ => {}
+            }
+        }
+    }
+
+    // Reads the rest of a word, which may contain any character besides delimiters. In
+    // particular `#` only has a special meaning at the start of a token, so `a#b` is a single
+    // identifier.
+    fn read_word(&mut self) -> TokenType<&'a str> {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                c if self.is_delimiter(c) => break,
                 // Could be a quote within a word, we should handle escaping it accordingly
                 // (even though its a bit odd)
                 '\\' => {
@@ -289,7 +829,27 @@ impl<'a> Lexer<'a> {
             };
         }
 
-        match self.slice() {
+        let folded;
+        let word = if self.fold_case {
+            folded = self.slice().to_lowercase();
+            folded.as_str()
+        } else {
+            self.slice()
+        };
+
+        // Only the core forms get their own token. Derived forms like `and`, `or`, `when`
+        // or `let*` are macros, and have to stay identifiers so that they can be expanded.
+        match word {
+            "=>" if self.arrows => TokenType::Arrow,
+            keyword
+                if self
+                    .keywords
+                    .as_ref()
+                    .map_or(false, |keywords| !keywords.contains(keyword))
+                    && !matches!(keyword, INFINITY | NEG_INFINITY | NAN | NEG_NAN) =>
+            {
+                self.read_identifier()
+            }
             "define" | "defn" | "#%define" => TokenType::Define,
             "let" => TokenType::Let,
             "%plain-let" => TokenType::TestLet,
@@ -300,7 +860,7 @@ impl<'a> Lexer<'a> {
             // "unquote" => TokenType::Unquote,
             "syntax-rules" => TokenType::SyntaxRules,
             "define-syntax" => TokenType::DefineSyntax,
-            "..." => TokenType::Ellipses,
+            "..." if !self.peculiar_identifiers => TokenType::Ellipses,
             "set!" => TokenType::Set,
             "require" => TokenType::Require,
             "if" => TokenType::If,
@@ -308,15 +868,43 @@ impl<'a> Lexer<'a> {
             NEG_INFINITY => TokenType::Number(RealLiteral::Float(f64::NEG_INFINITY).into()),
             NAN => TokenType::Number(RealLiteral::Float(f64::NAN).into()),
             NEG_NAN => TokenType::Number(RealLiteral::Float(f64::NAN).into()),
-            identifier => {
-                if identifier.len() > 1 && identifier.starts_with('+') && self.queued.is_none() {
-                    self.queued = Some(TokenType::Identifier(&identifier[1..]));
-                    TokenType::Identifier("+")
-                } else {
-                    TokenType::Identifier(identifier)
+            _ => self.read_identifier(),
+        }
+    }
+
+    // The token keeps the bars and escapes as written, they are decoded by
+    // `bar_identifier_name` once the token is owned.
+    fn read_bar_identifier(&mut self) -> Result<TokenType<&'a str>> {
+        // Skip the opening bar
+        self.eat();
+
+        loop {
+            match self.eat() {
+                Some('|') => break,
+                Some('\\') => {
+                    self.eat();
                 }
+                Some(_) => {}
+                None => return Err(TokenError::IncompleteIdentifier),
             }
         }
+
+        let identifier = self.slice();
+        match decode_symbol_escapes(&identifier[1..identifier.len() - 1]) {
+            Some(_) => Ok(TokenType::Identifier(identifier)),
+            None => Err(TokenError::InvalidEscape),
+        }
+    }
+
+    fn read_identifier(&mut self) -> TokenType<&'a str> {
+        let identifier = self.slice();
+
+        if identifier.len() > 1 && identifier.starts_with('+') && self.queued.is_none() {
+            self.queued = Some(TokenType::Identifier(&identifier[1..]));
+            TokenType::Identifier("+")
+        } else {
+            TokenType::Identifier(identifier)
+        }
     }
 }
 
@@ -332,6 +920,9 @@ impl<'a> Lexer<'a> {
     }
 }
 
+/// The tokens of an input, along with their source and span. Iterate over `&mut stream` to
+/// read some tokens and still have the stream afterwards, e.g. for
+/// [`TokenStream::remaining`].
 pub struct TokenStream<'a> {
     lexer: Lexer<'a>,
     skip_comments: bool,
@@ -347,6 +938,116 @@ impl<'a> TokenStream<'a> {
         }
     }
 
+    pub fn from_lexer(lexer: Lexer<'a>, skip_comments: bool, source_id: Option<SourceId>) -> Self {
+        Self {
+            lexer,
+            skip_comments,
+            source_id,
+        }
+    }
+
+    pub(crate) fn lexer_mut(&mut self) -> &mut Lexer<'a> {
+        &mut self.lexer
+    }
+
+    /// See [`Lexer::line_column`].
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        self.lexer.line_column(offset)
+    }
+
+    /// See [`Lexer::reset`].
+    pub fn reset(&mut self, input: &'a str) {
+        self.lexer.reset(input);
+    }
+
+    /// See [`Lexer::depth`].
+    pub fn depth(&self) -> usize {
+        self.lexer.depth()
+    }
+
+    /// See [`Lexer::remaining`].
+    pub fn remaining(&self) -> &'a str {
+        self.lexer.remaining()
+    }
+
+    /// See [`Lexer::diagnostics`].
+    pub fn diagnostics(&self) -> &[(crate::span::Span, TokenError)] {
+        self.lexer.diagnostics()
+    }
+
+    /// Groups the tokens of every top-level form in the input, together with the span
+    /// covering the whole form. Reader prefixes like `'` are grouped with the datum they
+    /// apply to, and comments are dropped.
+    pub fn split_forms_with_spans(
+        mut self,
+    ) -> Result<Vec<(crate::span::Span, Vec<Token<'a, &'a str>>)>> {
+        let mut forms = Vec::new();
+        let mut current: Vec<Token<'a, &'a str>> = Vec::new();
+        let mut depth = 0usize;
+
+        while let Some(token) = self.lexer.next() {
+            let token = Token::new(
+                token?,
+                self.lexer.slice(),
+                self.lexer.span(),
+                self.source_id,
+            );
+
+            if token.ty.is_open_delimiter() {
+                depth += 1;
+            } else if token.ty.is_close_delimiter() {
+                depth = depth.checked_sub(1).ok_or(TokenError::UnbalancedClose)?;
+            } else if token.ty.is_trivia() {
+                continue;
+            }
+
+            // A datum comment makes up a form together with the datum it comments out
+            let complete = depth == 0
+                && !token.ty.is_reader_prefix()
+                && !matches!(token.ty, TokenType::DatumComment);
+
+            current.push(token);
+
+            if complete {
+                let span =
+                    crate::span::Span::merge(current[0].span, current[current.len() - 1].span);
+                forms.push((span, core::mem::take(&mut current)));
+            }
+        }
+
+        if current.is_empty() {
+            Ok(forms)
+        } else {
+            Err(TokenError::IncompleteForm)
+        }
+    }
+
+    /// Every span each identifier occurs at, in order, for finding all references to a name.
+    /// This is purely lexical: shadowing and quoted symbols aren't told apart.
+    pub fn identifier_occurrences(self) -> BTreeMap<&'a str, Vec<crate::span::Span>> {
+        let mut occurrences: BTreeMap<&'a str, Vec<crate::span::Span>> = BTreeMap::new();
+
+        for token in self {
+            if let TokenType::Identifier(name) = token.ty {
+                occurrences.entry(name).or_default().push(token.span);
+            }
+        }
+
+        occurrences
+    }
+
+    /// How many tokens of each [`TokenCategory`] there are, to get a rough idea of what an
+    /// input consists of. Input that doesn't lex counts as [`TokenCategory::Error`].
+    pub fn category_counts(self) -> BTreeMap<TokenCategory, usize> {
+        let mut counts = BTreeMap::new();
+
+        for token in self {
+            *counts.entry(token.category()).or_default() += 1;
+        }
+
+        counts
+    }
+
     pub fn into_owned<T, F: ToOwnedString<T>>(self, adapter: F) -> OwnedTokenStream<'a, T, F> {
         OwnedTokenStream {
             stream: self,
@@ -367,17 +1068,33 @@ impl<'a, T, F: ToOwnedString<T>> Iterator for OwnedTokenStream<'a, T, F> {
 
     fn next(&mut self) -> Option<Self::Item> {
         self.stream.next().map(|x| Token {
-            ty: x.ty.map(|x| self.adapter.own(x)),
+            ty: x.ty.map(|x| {
+                if let Some(name) = bar_identifier_name(x) {
+                    self.adapter.own(&name)
+                } else if self.stream.lexer.fold_case() {
+                    self.adapter.own(&x.to_lowercase())
+                } else {
+                    self.adapter.own(x)
+                }
+            }),
             source: x.source,
             span: x.span,
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
 }
 
 impl<'a, T, F: ToOwnedString<T>> OwnedTokenStream<'a, T, F> {
     pub fn offset(&self) -> usize {
         self.stream.lexer.span().end
     }
+
+    pub(crate) fn lexer_mut(&mut self) -> &mut Lexer<'a> {
+        self.stream.lexer_mut()
+    }
 }
 impl<'a> Iterator for TokenStream<'a> {
     type Item = Token<'a, &'a str>;
@@ -392,49 +1109,201 @@ impl<'a> Iterator for TokenStream<'a> {
             let token = Token::new(token, self.lexer.slice(), self.lexer.span(), self.source_id);
             match token.ty {
                 // TokenType::Space => self.next(),
-                TokenType::Comment if self.skip_comments => self.next(),
+                TokenType::Comment(_) if self.skip_comments => self.next(),
                 // TokenType::DocComment if self.skip_doc_comments => self.next(),
                 _ => Some(token),
             }
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lexer.size_hint()
+    }
 }
 
-pub type Result<T> = std::result::Result<T, TokenError>;
+pub type Result<T> = core::result::Result<T, TokenError>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum TokenError {
     UnexpectedChar(char),
-    IncompleteString,
+    /// A raw control character outside of a string literal, which is almost always a
+    /// copy and paste mistake. It ends the token before it, like whitespace would.
+    ControlCharacter(char),
+    /// The input ended inside a string literal. Holds the contents read so far, and whether
+    /// the input ended right after a `\` starting an escape, so that a REPL can ask for
+    /// more input and carry on.
+    IncompleteString {
+        partial: String,
+        in_escape: bool,
+    },
     InvalidEscape,
+    /// The input ended inside of a `|...|` identifier.
+    IncompleteIdentifier,
     InvalidCharacter,
     MalformedHexInteger,
     MalformedOctalInteger,
     MalformedBinaryInteger,
     MalformedByteEscape,
+    InvalidNumber,
+    UnbalancedClose,
+    IncompleteForm,
+    InvalidHashSyntax(String),
+    NumberTooLong,
+    TokenTooLong,
+    NestingTooDeep,
 }
 
-impl<'a> Iterator for Lexer<'a> {
-    type Item = Result<TokenType<&'a str>>;
+/// The name of a `|...|` identifier like `|hello world|` with its escapes decoded, or
+/// `None` if `identifier` isn't written with bars.
+pub fn bar_identifier_name(identifier: &str) -> Option<String> {
+    let inner = identifier.strip_prefix('|')?.strip_suffix('|')?;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(t) = self.queued.take() {
-            return Some(Ok(t));
-        }
-        // Crunch until the next input
+    decode_symbol_escapes(inner)
+}
+
+// The escapes of R7RS symbols, `None` if one is malformed
+fn decode_symbol_escapes(inner: &str) -> Option<String> {
+    let mut name = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            name.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next()? {
+            'x' => {
+                let (digits, rest) = chars.as_str().split_once(';')?;
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return None;
+                }
+
+                chars = rest.chars();
+                u32::from_str_radix(digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)?
+            }
+            'a' => '\x07',
+            'b' => '\x08',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            c @ ('|' | '\\' | '"') => c,
+            _ => return None,
+        };
+
+        name.push(escaped);
+    }
+
+    Some(name)
+}
+
+/// Whether `c` ends the word, number or `#` syntax before it, whatever the lexer's options:
+/// whitespace, brackets, and the characters starting a string or a reader prefix. A lexer
+/// also stops at its comment character, which can be configured.
+pub fn is_delimiter(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '(' | ')' | '[' | ']' | '{' | '}' | '"' | '\'' | '`' | ','
+        )
+        || is_stray_control(c)
+}
+
+// Tabs and line breaks are the only control characters that belong in source code. The
+// others that count as whitespace, like form feeds, are skipped as such.
+fn is_stray_control(c: char) -> bool {
+    c.is_control() && !c.is_whitespace()
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<TokenType<&'a str>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = match self.read_token()? {
+            Ok(ty) if !ty.is_trivia() && self.exceeds_max_token_len() => {
+                Err(TokenError::TokenTooLong)
+            }
+            Err(TokenError::IncompleteString { partial, in_escape }) if self.partial_strings => {
+                let error = TokenError::IncompleteString {
+                    partial: partial.clone(),
+                    in_escape,
+                };
+                let span = crate::span::Span::new(self.token_start, self.token_end, None);
+                self.diagnostics.push((span, error));
+
+                Ok(TokenType::PartialString(partial))
+            }
+            token => token,
+        };
+
+        if let Ok(ty) = &token {
+            if ty.is_open_delimiter() {
+                if self.max_depth.map_or(false, |max| self.depth >= max) {
+                    return Some(Err(TokenError::NestingTooDeep));
+                }
+
+                self.depth += 1;
+            } else if ty.is_close_delimiter() {
+                self.depth = self.depth.saturating_sub(1);
+            }
+        }
+
+        Some(token)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every token takes up at least one byte of the remaining input, except for a
+        // queued token whose input has already been consumed.
+        let remaining = self.source.len() - self.token_end;
+
+        (0, Some(remaining + self.queued.is_some() as usize))
+    }
+}
+
+impl<'a> Lexer<'a> {
+    fn read_token(&mut self) -> Option<Result<TokenType<&'a str>>> {
+        if let Some(t) = self.queued.take() {
+            return Some(Ok(t));
+        }
+        if self.trivia && self.chars.peek().map_or(false, |c| c.is_whitespace()) {
+            let start = self.token_end;
+            self.consume_whitespace();
+            self.token_start = start;
+
+            return Some(Ok(TokenType::Whitespace));
+        }
+
+        // Crunch until the next input
         self.consume_whitespace();
 
         self.token_start = self.token_end;
 
         match self.chars.peek() {
-            Some(';') => {
-                self.eat();
+            Some(&c) if self.comment_char == Some(c) => {
+                let semicolons = self.source[self.token_start..]
+                    .chars()
+                    .take_while(|&next| next == c)
+                    .count();
+
                 self.read_rest_of_line();
-                Some(Ok(TokenType::Comment))
+                Some(Ok(TokenType::Comment(CommentLevel::from_semicolons(
+                    semicolons,
+                ))))
             }
 
             Some('"') => Some(self.read_string()),
 
+            Some('{') if self.curly_infix => {
+                self.eat();
+                Some(Ok(TokenType::OpenCurly))
+            }
+            Some('}') if self.curly_infix => {
+                self.eat();
+                Some(Ok(TokenType::CloseCurly))
+            }
+
             Some('(') | Some('[') | Some('{') => {
                 self.eat();
                 Some(Ok(TokenType::OpenParen))
@@ -454,6 +1323,10 @@ impl<'a> Iterator for Lexer<'a> {
                 self.eat();
                 Some(Ok(TokenType::QuasiQuote))
             }
+            Some(',') if self.comma_separators => {
+                self.eat();
+                Some(Ok(TokenType::Comma))
+            }
             Some(',') => {
                 self.eat();
 
@@ -465,28 +1338,571 @@ impl<'a> Iterator for Lexer<'a> {
                     Some(Ok(TokenType::Unquote))
                 }
             }
+            // Like in R7RS, a sign directly followed by a digit is part of the number, no matter
+            // what came before it: `(f -1)` and `(-1)` both read the literal `-1`. A sign on its
+            // own is an identifier, so `(- 1)` subtracts.
             Some('+') | Some('-') => {
                 self.eat();
                 match self.chars.peek() {
-                    Some(&c) if c.is_numeric() => Some(Ok(self.read_number())),
+                    Some(&c) if c.is_numeric() => Some(self.read_number()),
+                    Some('.') if self.at_leading_dot_number() => Some(self.read_number()),
                     _ => Some(Ok(self.read_word())),
                 }
             }
             Some('#') => {
                 self.eat();
+
+                // Directives don't produce a token, they only affect how the rest is read
+                if let Some(fold_case) = self.read_fold_case_directive() {
+                    self.fold_case = fold_case;
+                    return self.read_token();
+                }
+
                 Some(self.read_hash_value())
             }
 
+            Some('.') if self.at_leading_dot_number() => Some(self.read_number()),
+
+            Some('|') => Some(self.read_bar_identifier()),
+
+            // Only skipped at the very start of the input
+            Some(&BOM) => self.eat().map(|c| Err(TokenError::UnexpectedChar(c))),
+
+            Some(&c) if is_stray_control(c) => {
+                self.eat();
+                Some(Err(TokenError::ControlCharacter(c)))
+            }
+
             Some(c) if !c.is_whitespace() && !c.is_numeric() || *c == '_' => {
                 Some(Ok(self.read_word()))
             }
-            Some(c) if c.is_numeric() => Some(Ok(self.read_number())),
+            Some(c) if c.is_numeric() => Some(self.read_number()),
             Some(_) => self.eat().map(|e| Err(TokenError::UnexpectedChar(e))),
             None => None,
         }
     }
 }
 
+/// The average number of source bytes per token that [`tokenize`] reserves room for. The
+/// lexer's upper bound assumes single byte tokens, which would mostly be wasted space in
+/// typical code, where a short name or number comes with a bracket and a space or two.
+/// Guessing a little low only costs a final reallocation.
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Lexes all of `input`, skipping comments and stopping at the first error.
+pub fn tokenize(input: &str) -> Result<Vec<Token<'_, &str>>> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::with_capacity(lexer.size_hint().1.unwrap_or_default() / BYTES_PER_TOKEN);
+
+    while let Some(token) = lexer.next() {
+        let ty = token?;
+
+        if let TokenType::Comment(_) = ty {
+            continue;
+        }
+
+        tokens.push(Token::new(ty, lexer.slice(), lexer.span(), None));
+    }
+
+    Ok(tokens)
+}
+
+/// The tokens of an input split into their kinds and a parallel source map of where each
+/// one came from, see [`tokenize_with_source_map`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceMapped<'a> {
+    pub tokens: Vec<TokenType<&'a str>>,
+    /// `spans[i]` is the span of `tokens[i]` in the original input.
+    pub spans: Vec<crate::span::Span>,
+}
+
+impl<'a> SourceMapped<'a> {
+    /// The span in the original input of the token at `index`.
+    pub fn span_of(&self, index: usize) -> Option<crate::span::Span> {
+        self.spans.get(index).copied()
+    }
+}
+
+/// Like [`tokenize`], but keeps the token kinds and their spans in separate vectors, so that
+/// tools rewriting the tokens can still map them back to the source.
+pub fn tokenize_with_source_map(input: &str) -> Result<SourceMapped<'_>> {
+    let mut lexer = Lexer::new(input);
+    let mut mapped = SourceMapped {
+        tokens: Vec::new(),
+        spans: Vec::new(),
+    };
+
+    while let Some(token) = lexer.next() {
+        let ty = token?;
+
+        if let TokenType::Comment(_) = ty {
+            continue;
+        }
+
+        let span = lexer.span();
+        mapped.tokens.push(ty);
+        mapped
+            .spans
+            .push(crate::span::Span::new(span.start, span.end, None));
+    }
+
+    Ok(mapped)
+}
+
+/// Everything the lexer produced for an input, see [`lex`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lexed<'a> {
+    pub tokens: Vec<Token<'a, &'a str>>,
+    pub errors: Vec<(crate::span::Span, TokenError)>,
+}
+
+/// Lexes all of `input`, skipping comments. Unlike [`tokenize`], errors don't stop
+/// the lexer: each one is collected with its span and lexing carries on after it.
+pub fn lex(input: &str) -> Lexed<'_> {
+    let mut lexer = Lexer::new(input);
+    let mut lexed = Lexed {
+        tokens: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    while let Some(token) = lexer.next() {
+        match token {
+            Ok(TokenType::Comment(_)) => {}
+            Ok(ty) => lexed
+                .tokens
+                .push(Token::new(ty, lexer.slice(), lexer.span(), None)),
+            Err(e) => {
+                let span = lexer.span();
+                lexed
+                    .errors
+                    .push((crate::span::Span::new(span.start, span.end, None), e));
+            }
+        }
+    }
+
+    lexed
+}
+
+/// The tokens of a whole input, lexed up front with [`lex`], with a cursor for looking
+/// ahead and backtracking without running the lexer again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenBuffer<'a> {
+    lexed: Lexed<'a>,
+    position: usize,
+}
+
+impl<'a> TokenBuffer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            lexed: lex(input),
+            position: 0,
+        }
+    }
+
+    /// The token at `index`, regardless of the cursor.
+    pub fn get(&self, index: usize) -> Option<&Token<'a, &'a str>> {
+        self.lexed.tokens.get(index)
+    }
+
+    /// The tokens in `range`, regardless of the cursor. Panics if it is out of bounds.
+    pub fn slice(&self, range: Range<usize>) -> &[Token<'a, &'a str>] {
+        &self.lexed.tokens[range]
+    }
+
+    /// The token at the cursor, without moving it.
+    pub fn peek(&self) -> Option<&Token<'a, &'a str>> {
+        self.get(self.position)
+    }
+
+    /// The type of the token at the cursor, without moving it.
+    pub fn peek_kind(&self) -> Option<&TokenType<&'a str>> {
+        self.peek().map(Token::typ)
+    }
+
+    /// The token at the cursor, moving the cursor past it.
+    pub fn advance(&mut self) -> Option<&Token<'a, &'a str>> {
+        let token = self.lexed.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    /// The index of the token at the cursor, to go back to later with
+    /// [`TokenBuffer::backtrack`].
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the cursor back to `position`, as returned by [`TokenBuffer::position`].
+    /// Panics if the cursor hasn't got that far yet.
+    pub fn backtrack(&mut self, position: usize) {
+        assert!(
+            position <= self.position,
+            "can't backtrack forwards from {} to {position}",
+            self.position
+        );
+        self.position = position;
+    }
+
+    /// Whether the cursor is past the last token.
+    pub fn is_at_end(&self) -> bool {
+        self.position >= self.lexed.tokens.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lexed.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lexed.tokens.is_empty()
+    }
+
+    /// The errors found while lexing, see [`Lexed::errors`].
+    pub fn errors(&self) -> &[(crate::span::Span, TokenError)] {
+        &self.lexed.errors
+    }
+}
+
+/// Pairs the span of every token in `input`, comments included, with the scope
+/// from [`TokenType::scope`]. Input the lexer rejects is reported as
+/// `invalid.illegal` rather than ending the scan.
+pub fn highlight(input: &str) -> Vec<(crate::span::Span, &'static str)> {
+    let mut lexer = Lexer::new(input);
+    let mut scopes = Vec::new();
+
+    while let Some(token) = lexer.next() {
+        let scope = match token {
+            Ok(ty) => ty.scope(),
+            Err(_) => TokenType::<&str>::Error.scope(),
+        };
+
+        let span = lexer.span();
+        scopes.push((crate::span::Span::new(span.start, span.end, None), scope));
+    }
+
+    scopes
+}
+
+/// Checks that every delimiter in `input` is closed, and that nothing is closed
+/// that wasn't opened. Reports the first problem found.
+pub fn check_balanced(input: &str) -> Result<()> {
+    let mut lexer = Lexer::new(input);
+
+    loop {
+        let depth = lexer.depth();
+
+        match lexer.next() {
+            Some(Ok(ty)) if ty.is_close_delimiter() && depth == 0 => {
+                return Err(TokenError::UnbalancedClose)
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e),
+            None if lexer.depth() > 0 => return Err(TokenError::IncompleteForm),
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Whether `input` lexes successfully and all of its delimiters match, e.g. to decide
+/// whether a REPL should keep reading lines before evaluating.
+pub fn is_balanced(input: &str) -> bool {
+    check_balanced(input).is_ok()
+}
+
+/// What [`StreamingLexer::next_token`] found.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LexPoll {
+    Ready(TokenType<String>, crate::span::Span),
+    /// The input fed so far ends in the middle of a token, or has no more tokens.
+    Pending,
+    Error(TokenError, crate::span::Span),
+}
+
+/// A lexer that is fed its input a chunk at a time, e.g. line by line in a REPL. A token
+/// running up to the end of the input fed so far is held back, since the next chunk might
+/// continue it, until [`StreamingLexer::finish`] says that no more input is coming. Spans
+/// are offsets into all the input fed so far, and comments are skipped.
+///
+/// A held back token is lexed again from its start once more input comes in. An
+/// unterminated string is only lexed again once a chunk with a `"` in it arrives, but an
+/// identifier or number fed a few characters at a time is lexed again for every chunk.
+pub struct StreamingLexer {
+    /// Lexes the buffer, with the options the caller configured it with.
+    lexer: Lexer<'static>,
+    /// The input that hasn't been turned into tokens yet.
+    buffer: String,
+    /// How much input came before `buffer`.
+    consumed: usize,
+    ready: VecDeque<LexPoll>,
+    depth: usize,
+    /// The length of the unterminated string that `buffer` holds, when the last call held
+    /// one back.
+    held_string: Option<usize>,
+    finished: bool,
+}
+
+impl fmt::Debug for StreamingLexer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingLexer")
+            .field("buffer", &self.buffer)
+            .field("consumed", &self.consumed)
+            .field("ready", &self.ready)
+            .field("depth", &self.depth)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for StreamingLexer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingLexer {
+    pub fn new() -> Self {
+        Self::with_lexer(Lexer::new(""))
+    }
+
+    /// Lexes with the options that `lexer` was configured with, e.g.
+    /// `StreamingLexer::with_lexer(Lexer::new("").with_curly_infix())`. The input of
+    /// `lexer` is ignored.
+    pub fn with_lexer(lexer: Lexer<'static>) -> Self {
+        Self {
+            lexer,
+            buffer: String::new(),
+            consumed: 0,
+            ready: VecDeque::new(),
+            depth: 0,
+            held_string: None,
+            finished: false,
+        }
+    }
+
+    pub fn feed(&mut self, chunk: &str) {
+        self.buffer.push_str(chunk);
+    }
+
+    /// Marks the end of the input, so that a token reaching up to it is complete.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// The number of delimiters opened by the tokens returned so far that haven't been
+    /// closed yet. A REPL has a complete form once this is back to zero.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The next token, [`LexPoll::Pending`] if more input is needed to tell what it is, or
+    /// `None` once all the input is used up after [`StreamingLexer::finish`].
+    pub fn next_token(&mut self) -> Option<LexPoll> {
+        if self.ready.is_empty() {
+            self.lex_buffer();
+        }
+
+        match self.ready.pop_front() {
+            Some(poll) => {
+                if let LexPoll::Ready(ty, _) = &poll {
+                    if ty.is_open_delimiter() {
+                        self.depth += 1;
+                    } else if ty.is_close_delimiter() {
+                        self.depth = self.depth.saturating_sub(1);
+                    }
+                }
+
+                Some(poll)
+            }
+            None if self.finished => None,
+            None => Some(LexPoll::Pending),
+        }
+    }
+
+    // Lexes the complete tokens at the start of the buffer, and drops their input from it.
+    fn lex_buffer(&mut self) {
+        if let Some(len) = self.held_string.take() {
+            if !self.finished && !self.buffer[len..].contains('"') {
+                self.held_string = Some(len);
+                return;
+            }
+        }
+
+        let mut lexer: Lexer<'_> = self.lexer.clone();
+        lexer.reset(&self.buffer);
+        lexer.fold_case = self.lexer.fold_case;
+
+        let mut end = 0;
+        let mut held_back = false;
+        let mut held_string = false;
+
+        while let Some(result) = lexer.next() {
+            let span = lexer.span();
+
+            // These can't be continued by the next chunk, even if they end the buffer
+            let closed = matches!(
+                result,
+                Ok(TokenType::OpenParen
+                    | TokenType::CloseParen
+                    | TokenType::OpenCurly
+                    | TokenType::CloseCurly
+                    | TokenType::OpenVector
+                    | TokenType::OpenBytevector
+                    | TokenType::QuoteTick
+                    | TokenType::QuasiQuote
+                    | TokenType::UnquoteSplice
+                    | TokenType::StringLiteral(_))
+            );
+
+            if span.end == self.buffer.len() && !closed && !self.finished {
+                held_back = true;
+
+                held_string = matches!(
+                    result,
+                    Err(TokenError::IncompleteString { .. }) | Ok(TokenType::PartialString(_))
+                );
+
+                break;
+            }
+
+            end = span.end;
+
+            let span =
+                crate::span::Span::new(self.consumed + span.start, self.consumed + span.end, None);
+
+            match result {
+                Ok(TokenType::Comment(_)) => {}
+                Ok(ty) => self.ready.push_back(LexPoll::Ready(ty.to_owned(), span)),
+                Err(e) => self.ready.push_back(LexPoll::Error(e, span)),
+            }
+        }
+
+        // Only whitespace is left after the last token
+        if !held_back {
+            end = self.buffer.len();
+        }
+
+        self.lexer.fold_case = lexer.fold_case;
+        self.buffer.drain(..end);
+        self.consumed += end;
+        self.held_string = held_string.then(|| self.buffer.len());
+    }
+}
+
+// Splits off the `#e`/`#i` exactness and `#x`/`#d`/`#o`/`#b` radix prefixes of a number,
+// which may appear in either order, e.g. `#e#xff` or `#x#eff`. The exactness is `Some(true)`
+// for `#e`, `Some(false)` for `#i` and `None` if it wasn't specified.
+fn split_number_prefixes(mut s: &str) -> Option<(Option<bool>, u32, &str)> {
+    let mut exactness = None;
+    let mut radix = None;
+
+    while let Some(rest) = s.strip_prefix('#') {
+        let mut chars = rest.chars();
+
+        match chars.next()? {
+            'e' if exactness.is_none() => exactness = Some(true),
+            'i' if exactness.is_none() => exactness = Some(false),
+            'x' if radix.is_none() => radix = Some(16),
+            'd' if radix.is_none() => radix = Some(10),
+            'o' if radix.is_none() => radix = Some(8),
+            'b' if radix.is_none() => radix = Some(2),
+            _ => return None,
+        }
+
+        s = chars.as_str();
+    }
+
+    Some((exactness, radix.unwrap_or(10), s))
+}
+
+fn int_literal(n: BigInt) -> IntLiteral {
+    n.to_isize()
+        .map(IntLiteral::Small)
+        .unwrap_or_else(|| IntLiteral::Big(Box::new(n)))
+}
+
+fn rational_literal(r: BigRational) -> RealLiteral {
+    let (n, d) = r.into_raw();
+
+    if d.is_one() {
+        RealLiteral::Int(int_literal(n))
+    } else {
+        RealLiteral::Rational(int_literal(n), int_literal(d))
+    }
+}
+
+// Reads a decimal literal like `-1.25e3` exactly, rather than going through an `f64`,
+// so that `#e0.1` is `1/10`. The exponent is held to `max_digits`, since a short literal
+// like `#e1e999999999` would otherwise be a billion digits long.
+fn parse_exact_decimal(s: &str, max_digits: usize) -> Result<RealLiteral> {
+    let (mantissa, exponent) = match s.find(|c| c == 'e' || c == 'E') {
+        Some(idx) => (
+            &s[..idx],
+            s[idx + 1..].parse::<i32>().map_err(|e| match e.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => TokenError::NumberTooLong,
+                _ => TokenError::InvalidNumber,
+            })?,
+        ),
+        None => (s, 0),
+    };
+
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let numerator: BigInt = format!("{whole}{fraction}")
+        .parse()
+        .map_err(|_| TokenError::InvalidNumber)?;
+    let scale = i32::try_from(fraction.len())
+        .ok()
+        .and_then(|len| exponent.checked_sub(len))
+        .filter(|scale| scale.unsigned_abs() as usize <= max_digits)
+        .ok_or(TokenError::NumberTooLong)?;
+
+    let value = if scale >= 0 {
+        BigRational::from_integer(numerator * num::pow(BigInt::from(10), scale as usize))
+    } else {
+        BigRational::new(numerator, num::pow(BigInt::from(10), (-scale) as usize))
+    };
+
+    Ok(rational_literal(value))
+}
+
+fn exact_real(real: RealLiteral) -> Option<RealLiteral> {
+    match real {
+        RealLiteral::Float(f) => BigRational::from_float(f).map(rational_literal),
+        other => Some(other),
+    }
+}
+
+fn inexact_real(real: RealLiteral) -> RealLiteral {
+    match real {
+        RealLiteral::Int(i) => RealLiteral::Float(BigInt::from(i).to_f64().unwrap_or(f64::NAN)),
+        RealLiteral::Rational(n, d) => RealLiteral::Float(
+            BigInt::from(n).to_f64().unwrap_or(f64::NAN)
+                / BigInt::from(d).to_f64().unwrap_or(f64::NAN),
+        ),
+        float @ RealLiteral::Float(_) => float,
+    }
+}
+
+fn into_exact(number: NumberLiteral, source: &str, max_digits: usize) -> Result<NumberLiteral> {
+    match number {
+        NumberLiteral::Real(RealLiteral::Float(_)) => {
+            parse_exact_decimal(source, max_digits).map(Into::into)
+        }
+        NumberLiteral::Real(real) => Ok(real.into()),
+        NumberLiteral::Complex(re, im) => exact_real(re)
+            .zip(exact_real(im))
+            .map(|(re, im)| NumberLiteral::Complex(re, im))
+            .ok_or(TokenError::InvalidNumber),
+    }
+}
+
+fn into_inexact(number: NumberLiteral) -> NumberLiteral {
+    match number {
+        NumberLiteral::Real(real) => inexact_real(real).into(),
+        NumberLiteral::Complex(re, im) => {
+            NumberLiteral::Complex(inexact_real(re), inexact_real(im))
+        }
+    }
+}
+
 // Split the string by + and -. Returns at most 2 elements or `None` if there were more than 2.
 fn split_into_complex<'a>(s: &'a str) -> Option<SmallVec<[NumPart<'a>; 2]>> {
     let classify_num_part = |s: &'a str| -> NumPart<'a> {
@@ -502,7 +1918,7 @@ fn split_into_complex<'a>(s: &'a str) -> Option<SmallVec<[NumPart<'a>; 2]>> {
         .take(3)
         .collect();
     let parts = match idxs.as_slice() {
-        [] | [0] => SmallVec::from_iter(std::iter::once(s).map(classify_num_part)),
+        [] | [0] => SmallVec::from_iter(core::iter::once(s).map(classify_num_part)),
         [idx] | [0, idx] => {
             SmallVec::from_iter([&s[0..*idx], &s[*idx..]].into_iter().map(classify_num_part))
         }
@@ -559,15 +1975,27 @@ fn parse_real(s: &str) -> Option<RealLiteral> {
     } else if let Some(p) = frac_position {
         let (n_str, d_str) = s.split_at(p);
         let d_str = &d_str[1..];
-        let n: IntLiteral = n_str.parse().ok()?;
-        let d: IntLiteral = d_str.parse().ok()?;
-        Some(RealLiteral::Rational(n, d))
+        let n: BigInt = n_str.parse().ok()?;
+        let d: BigInt = d_str.parse().ok()?;
+        if d.is_zero() {
+            return None;
+        }
+        Some(rational_literal(BigRational::new(n, d)))
     } else {
         let int: IntLiteral = s.parse().ok()?;
         Some(RealLiteral::Int(int))
     }
 }
 
+fn has_zero_denominator(s: &str) -> bool {
+    match s.split_once('/') {
+        Some((n, d)) => {
+            n.parse::<BigInt>().is_ok() && d.parse::<BigInt>().map_or(false, |d| d.is_zero())
+        }
+        None => false,
+    }
+}
+
 fn parse_number(s: &str) -> Option<NumberLiteral> {
     match split_into_complex(s)?.as_slice() {
         [NumPart::Real(x)] => parse_real(x).map(NumberLiteral::from),
@@ -587,8 +2015,6 @@ fn parse_number(s: &str) -> Option<NumberLiteral> {
 
 #[cfg(test)]
 mod lexer_tests {
-    use std::str::FromStr;
-
     use super::*;
     use crate::span::Span;
     use crate::tokens::{IntLiteral, TokenType::*};
@@ -948,11 +2374,7 @@ mod lexer_tests {
                     span: Span::new(45, 46, None),
                 },
                 Token {
-                    ty: RealLiteral::Rational(
-                        IntLiteral::from_str("11111111111111111111").unwrap(),
-                        IntLiteral::from_str("22222222222222222222").unwrap(),
-                    )
-                    .into(),
+                    ty: RealLiteral::Rational(IntLiteral::Small(1), IntLiteral::Small(2)).into(),
                     source: "11111111111111111111/22222222222222222222",
                     span: Span::new(63, 104, None),
                 },
@@ -1261,4 +2683,1513 @@ mod lexer_tests {
 
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn test_curly_infix() {
+        let got: Vec<_> =
+            TokenStream::from_lexer(Lexer::new("{1 + 2}").with_curly_infix(), true, None)
+                .map(|token| token.ty)
+                .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                OpenCurly,
+                IntLiteral::Small(1).into(),
+                Identifier("+"),
+                IntLiteral::Small(2).into(),
+                CloseCurly,
+            ]
+        );
+
+        let got: Vec<_> = TokenStream::new("{1 + 2}", true, None)
+            .map(|token| token.ty)
+            .collect();
+
+        assert_eq!(got.first(), Some(&OpenParen));
+        assert_eq!(got.last(), Some(&CloseParen));
+    }
+
+    #[test]
+    fn test_vector_literal() {
+        let got: Vec<_> = TokenStream::new("#(1 2)", true, None).collect();
+
+        assert_eq!(
+            got.as_slice(),
+            &[
+                Token {
+                    ty: OpenVector,
+                    source: "#(",
+                    span: Span::new(0, 2, None),
+                },
+                Token {
+                    ty: IntLiteral::Small(1).into(),
+                    source: "1",
+                    span: Span::new(2, 3, None),
+                },
+                Token {
+                    ty: IntLiteral::Small(2).into(),
+                    source: "2",
+                    span: Span::new(4, 5, None),
+                },
+                Token {
+                    ty: CloseParen,
+                    source: ")",
+                    span: Span::new(5, 6, None),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exactness_prefixes() {
+        let got: Vec<_> = TokenStream::new(
+            "#e1.0 #e1.5 #e0.1 #i5 #i1/2 #x#eff #e#xff #i#b101",
+            true,
+            None,
+        )
+        .map(|token| token.ty)
+        .collect();
+
+        let expected: Vec<TokenType<&str>> = vec![
+            IntLiteral::Small(1).into(),
+            RealLiteral::Rational(IntLiteral::Small(3), IntLiteral::Small(2)).into(),
+            RealLiteral::Rational(IntLiteral::Small(1), IntLiteral::Small(10)).into(),
+            RealLiteral::Float(5.0).into(),
+            RealLiteral::Float(0.5).into(),
+            IntLiteral::Small(255).into(),
+            IntLiteral::Small(255).into(),
+            RealLiteral::Float(5.0).into(),
+        ];
+
+        assert_eq!(got, expected);
+
+        let mut s = Lexer::new("#efoo #i1x");
+        assert_eq!(s.next(), Some(Err(TokenError::InvalidNumber)));
+        assert_eq!(s.next(), Some(Err(TokenError::InvalidNumber)));
+    }
+
+    #[test]
+    fn test_split_forms_with_spans() {
+        let forms = TokenStream::new("(define x 1) ; comment\n'(a b)", false, None)
+            .split_forms_with_spans()
+            .unwrap();
+
+        assert_eq!(forms.len(), 2);
+
+        assert_eq!(forms[0].0, Span::new(0, 12, None));
+        assert_eq!(
+            forms[0].1.iter().map(|x| x.source).collect::<Vec<_>>(),
+            vec!["(", "define", "x", "1", ")"]
+        );
+
+        assert_eq!(forms[1].0, Span::new(23, 29, None));
+        assert_eq!(
+            forms[1].1.iter().map(|x| x.source).collect::<Vec<_>>(),
+            vec!["'", "(", "a", "b", ")"]
+        );
+
+        assert_eq!(
+            TokenStream::new("(a) (b", true, None).split_forms_with_spans(),
+            Err(TokenError::IncompleteForm)
+        );
+        assert_eq!(
+            TokenStream::new("(a))", true, None).split_forms_with_spans(),
+            Err(TokenError::UnbalancedClose)
+        );
+    }
+
+    #[test]
+    fn test_depth_and_balance() {
+        let mut lexer = Lexer::new("(define (f x) [g x");
+        let depths: Vec<usize> =
+            std::iter::from_fn(|| lexer.next().map(|_| lexer.depth())).collect();
+        assert_eq!(depths, vec![1, 1, 2, 2, 2, 1, 2, 2, 2]);
+
+        assert!(is_balanced("(define (f x) [g x]) '#(1 2)"));
+        assert!(is_balanced("\"(\" ; )"));
+        assert!(!is_balanced("(define (f x) [g x"));
+        assert!(!is_balanced("(f))"));
+
+        assert_eq!(check_balanced("(f))"), Err(TokenError::UnbalancedClose));
+        assert_eq!(check_balanced("(f"), Err(TokenError::IncompleteForm));
+        assert_eq!(check_balanced(""), Ok(()));
+    }
+
+    #[test]
+    fn test_fold_case_directives() {
+        let identifiers = |input: &str| {
+            TokenStream::new(input, true, None)
+                .into_owned(OwnedString)
+                .map(|token| token.ty)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            identifiers("FOO"),
+            vec![TokenType::Identifier("FOO".to_string())]
+        );
+        assert_eq!(
+            identifiers("#!fold-case FOO"),
+            vec![TokenType::Identifier("foo".to_string())]
+        );
+        assert_eq!(
+            identifiers("#!fold-case (DEFINE Bar) #!no-fold-case Baz"),
+            vec![
+                TokenType::OpenParen,
+                TokenType::Define,
+                TokenType::Identifier("bar".to_string()),
+                TokenType::CloseParen,
+                TokenType::Identifier("Baz".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_peculiar_identifiers() {
+        let tokens = |lexer: Lexer<'static>| {
+            TokenStream::from_lexer(lexer, true, None)
+                .map(|token| token.ty)
+                .collect::<Vec<_>>()
+        };
+
+        let expected: Vec<TokenType<&str>> = vec![
+            TokenType::Identifier("+"),
+            TokenType::Identifier("-"),
+            TokenType::Ellipses,
+            IntLiteral::Small(5).into(),
+            IntLiteral::Small(-5).into(),
+        ];
+        assert_eq!(tokens(Lexer::new("+ - ... +5 -5")), expected);
+
+        let expected: Vec<TokenType<&str>> = vec![
+            TokenType::Identifier("+"),
+            TokenType::Identifier("-"),
+            TokenType::Identifier("..."),
+            IntLiteral::Small(5).into(),
+            IntLiteral::Small(-5).into(),
+        ];
+        assert_eq!(
+            tokens(Lexer::new("+ - ... +5 -5").with_peculiar_identifiers()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_keywords() {
+        let got: Vec<_> = TokenStream::new(
+            "if begin set! quote define-syntax define let lambda return! syntax-rules require",
+            true,
+            None,
+        )
+        .map(|token| token.ty)
+        .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                If,
+                Begin,
+                Set,
+                Quote,
+                DefineSyntax,
+                Define,
+                Let,
+                Lambda,
+                Return,
+                SyntaxRules,
+                Require
+            ]
+        );
+
+        let got: Vec<_> = TokenStream::new(
+            "and or not when unless quasiquote let* letrec cond else",
+            true,
+            None,
+        )
+        .map(|token| token.ty)
+        .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Identifier("and"),
+                Identifier("or"),
+                Identifier("not"),
+                Identifier("when"),
+                Identifier("unless"),
+                Identifier("quasiquote"),
+                Identifier("let*"),
+                Identifier("letrec"),
+                Identifier("cond"),
+                Identifier("else"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ellipsis() {
+        let got: Vec<_> = TokenStream::new("(x ...) ... .. ....", true, None)
+            .map(|token| token.ty)
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                OpenParen,
+                Identifier("x"),
+                Ellipses,
+                CloseParen,
+                Ellipses,
+                Identifier(".."),
+                Identifier("...."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_configurable_keywords() {
+        let keywords = BTreeSet::from(["define", "if"]);
+
+        let got: Vec<_> = TokenStream::from_lexer(
+            Lexer::new("define if lambda fn list +inf.0").with_keywords(&keywords),
+            true,
+            None,
+        )
+        .map(|token| token.ty)
+        .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Define,
+                If,
+                Identifier("lambda"),
+                Identifier("fn"),
+                Identifier("list"),
+                RealLiteral::Float(f64::INFINITY).into(),
+            ]
+        );
+
+        let got: Vec<_> = TokenStream::new("define if lambda fn list", true, None)
+            .map(|token| token.ty)
+            .collect();
+
+        assert_eq!(got, vec![Define, If, Lambda, Lambda, Identifier("list")]);
+    }
+
+    #[test]
+    fn test_size_hint_and_tokenize() {
+        let input = "(define (f x) (+x 1)) ; comment";
+
+        let stream = TokenStream::new(input, true, None);
+        assert_eq!(stream.size_hint(), (0, Some(input.len())));
+
+        let tokens = tokenize(input).unwrap();
+        let collected: Vec<_> = TokenStream::new(input, true, None).collect();
+        assert_eq!(tokens, collected);
+        assert!(tokens.len() <= input.len());
+
+        let mut stream = TokenStream::new("+x", true, None);
+        assert_eq!(stream.next().map(|token| token.ty), Some(Identifier("+")));
+        assert_eq!(stream.size_hint(), (0, Some(1)));
+
+        assert_eq!(
+            tokenize("(a \"b"),
+            Err(TokenError::IncompleteString {
+                partial: "b".to_string(),
+                in_escape: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let first = String::from("(+x");
+        let second = String::from("FOO)");
+
+        let mut stream = TokenStream::from_lexer(Lexer::new(&first).with_curly_infix(), true, None);
+
+        assert_eq!(stream.next().map(|token| token.ty), Some(OpenParen));
+        assert_eq!(stream.next().map(|token| token.ty), Some(Identifier("+")));
+        assert_eq!(stream.depth(), 1);
+
+        stream.reset(&second);
+        assert_eq!(stream.depth(), 0);
+
+        let got: Vec<_> = stream.by_ref().collect();
+        assert_eq!(
+            got,
+            vec![
+                Token::new(Identifier("FOO"), "FOO", 0..3, None),
+                Token::new(CloseParen, ")", 3..4, None),
+            ]
+        );
+
+        stream.reset("{a}");
+        let got: Vec<_> = stream.map(|token| token.ty).collect();
+        assert_eq!(got, vec![OpenCurly, Identifier("a"), CloseCurly]);
+    }
+
+    #[test]
+    fn test_line_column() {
+        let lexer = Lexer::new("(a\n  (λ x))");
+
+        assert_eq!(lexer.line_column(0), (1, 1));
+        assert_eq!(lexer.line_column(1), (1, 2));
+        assert_eq!(lexer.line_column(3), (2, 1));
+        assert_eq!(lexer.line_column(5), (2, 3));
+        // `λ` is two bytes long, but a single column
+        assert_eq!(lexer.line_column(8), (2, 5));
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn test_grapheme_columns() {
+        // An `e` followed by a combining acute accent
+        let input = "(e\u{301} x)";
+        let offset = input.find('x').unwrap();
+
+        assert_eq!(Lexer::new(input).line_column(offset), (1, 5));
+        assert_eq!(
+            Lexer::new(input)
+                .with_column_unit(ColumnUnit::Graphemes)
+                .line_column(offset),
+            (1, 4)
+        );
+    }
+
+    #[test]
+    fn test_leading_dot_numbers() {
+        let got: Vec<_> = TokenStream::new(".5 -.5 +.25 (a . b) .a ...", true, None)
+            .map(|token| token.ty)
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                RealLiteral::Float(0.5).into(),
+                RealLiteral::Float(-0.5).into(),
+                RealLiteral::Float(0.25).into(),
+                OpenParen,
+                Identifier("a"),
+                Identifier("."),
+                Identifier("b"),
+                CloseParen,
+                Identifier(".a"),
+                Ellipses,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bytevector_literal() {
+        let got: Vec<_> = TokenStream::new("#u8(1 255)", true, None)
+            .map(|token| token.ty)
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                OpenBytevector,
+                IntLiteral::Small(1).into(),
+                IntLiteral::Small(255).into(),
+                CloseParen,
+            ]
+        );
+
+        for malformed in ["#u8 (1 2)", "#u(1 2)"] {
+            let mut lexer = Lexer::new(malformed);
+            assert_eq!(
+                lexer.next(),
+                Some(Err(TokenError::InvalidHashSyntax(
+                    malformed.split([' ', '(']).next().unwrap().to_string()
+                )))
+            );
+        }
+    }
+
+    #[test]
+    fn test_number_too_long() {
+        let input = format!("{} foo", "1".repeat(100_000));
+        let mut lexer = Lexer::new(&input);
+
+        assert_eq!(lexer.next(), Some(Err(TokenError::NumberTooLong)));
+        assert_eq!(lexer.next(), Some(Ok(Identifier("foo"))));
+
+        let mut lexer = Lexer::new("123 1234 #e1234").with_max_number_digits(3);
+
+        assert_eq!(lexer.next(), Some(Ok(IntLiteral::Small(123).into())));
+        assert_eq!(lexer.next(), Some(Err(TokenError::NumberTooLong)));
+        assert_eq!(lexer.next(), Some(Err(TokenError::NumberTooLong)));
+        assert_eq!(lexer.next(), None);
+
+        // Short literals whose exact value would still be enormous
+        let mut lexer =
+            Lexer::new("#e1e999999999 #e1e-999999999 #e1.5e-2147483648 #e1e99999999999 #e1e2");
+
+        assert_eq!(lexer.next(), Some(Err(TokenError::NumberTooLong)));
+        assert_eq!(lexer.next(), Some(Err(TokenError::NumberTooLong)));
+        assert_eq!(lexer.next(), Some(Err(TokenError::NumberTooLong)));
+        assert_eq!(lexer.next(), Some(Err(TokenError::NumberTooLong)));
+        assert_eq!(lexer.next(), Some(Ok(IntLiteral::Small(100).into())));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_invalid_hash_syntax() {
+        let mut lexer = Lexer::new("#zzz #tru (#foo) #%define #%plain-let ##gensym");
+
+        assert_eq!(
+            lexer.next(),
+            Some(Err(TokenError::InvalidHashSyntax("#zzz".to_string())))
+        );
+        assert_eq!(
+            lexer.next(),
+            Some(Err(TokenError::InvalidHashSyntax("#tru".to_string())))
+        );
+        assert_eq!(lexer.next(), Some(Ok(OpenParen)));
+        assert_eq!(
+            lexer.next(),
+            Some(Err(TokenError::InvalidHashSyntax("#foo".to_string())))
+        );
+        assert_eq!(lexer.next(), Some(Ok(CloseParen)));
+        assert_eq!(lexer.next(), Some(Ok(Define)));
+        assert_eq!(lexer.next(), Some(Ok(Identifier("#%plain-let"))));
+        assert_eq!(lexer.next(), Some(Ok(Identifier("##gensym"))));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_max_token_len() {
+        let input = format!(
+            "abc {} \"{}\" \"ok\" {}",
+            "a".repeat(10),
+            "b".repeat(10),
+            "1".repeat(10)
+        );
+        let mut lexer = Lexer::new(&input).with_max_token_len(5);
+
+        assert_eq!(lexer.next(), Some(Ok(Identifier("abc"))));
+        assert_eq!(lexer.next(), Some(Err(TokenError::TokenTooLong)));
+        assert_eq!(lexer.next(), Some(Err(TokenError::TokenTooLong)));
+        assert_eq!(lexer.next(), Some(Ok(StringLiteral("ok".to_string()))));
+        assert_eq!(lexer.next(), Some(Err(TokenError::TokenTooLong)));
+        assert_eq!(lexer.next(), None);
+
+        let input = format!("a{}; {}\nb", " ".repeat(10), "c".repeat(10));
+        let got: Vec<_> = Lexer::new(&input)
+            .with_trivia()
+            .with_max_token_len(5)
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Ok(Identifier("a")),
+                Ok(Whitespace),
+                Ok(Comment(CommentLevel::Inline)),
+                Ok(Identifier("b")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let mut lexer = Lexer::new("((a) ((b)))").with_max_depth(2);
+
+        let got: Vec<_> = std::iter::from_fn(|| lexer.next()).collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Ok(OpenParen),
+                Ok(OpenParen),
+                Ok(Identifier("a")),
+                Ok(CloseParen),
+                Ok(OpenParen),
+                Err(TokenError::NestingTooDeep),
+                Ok(Identifier("b")),
+                Ok(CloseParen),
+                Ok(CloseParen),
+                Ok(CloseParen),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_inside_identifier() {
+        let got: Vec<_> = TokenStream::new("a#b a# (f#t) #t", true, None)
+            .map(|token| token.ty)
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Identifier("a#b"),
+                Identifier("a#"),
+                OpenParen,
+                Identifier("f#t"),
+                CloseParen,
+                BooleanLiteral(true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_carriage_return_line_endings() {
+        for input in ["; comment\r\n(+ 1 2)", "; comment\r(+ 1 2)"] {
+            let got: Vec<_> = TokenStream::new(input, true, None)
+                .map(|token| token.ty)
+                .collect();
+
+            assert_eq!(
+                got,
+                vec![
+                    OpenParen,
+                    Identifier("+"),
+                    IntLiteral::Small(1).into(),
+                    IntLiteral::Small(2).into(),
+                    CloseParen,
+                ]
+            );
+        }
+
+        let input = "a\r\nb\rc\nd";
+        let lexer = Lexer::new(input);
+
+        assert_eq!(lexer.line_column(input.find('b').unwrap()), (2, 1));
+        assert_eq!(lexer.line_column(input.find('c').unwrap()), (3, 1));
+        assert_eq!(lexer.line_column(input.find('d').unwrap()), (4, 1));
+    }
+
+    #[test]
+    fn test_highlight_scopes() {
+        let input = "(if #t 42 \"yes\") ; done";
+        let scopes: Vec<_> = highlight(input)
+            .into_iter()
+            .map(|(span, scope)| (&input[std::ops::Range::from(span)], scope))
+            .collect();
+
+        assert_eq!(
+            scopes,
+            vec![
+                ("(", "punctuation.section.parens"),
+                ("if", "keyword.control"),
+                ("#t", "constant.language.boolean"),
+                ("42", "constant.numeric"),
+                ("\"yes\"", "string.quoted.double"),
+                (")", "punctuation.section.parens"),
+                ("; done", "comment.line"),
+            ]
+        );
+
+        assert_eq!(
+            highlight("'x #\\a"),
+            vec![
+                (Span::new(0, 1, None), "keyword.operator.quote"),
+                (Span::new(1, 2, None), "variable.other"),
+                (Span::new(3, 6, None), "constant.character"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_collects_tokens_and_errors() {
+        let lexed = lex("(a ; note\n #\\nope b \"unterminated");
+
+        assert_eq!(
+            lexed
+                .tokens
+                .iter()
+                .map(|t| t.ty.clone())
+                .collect::<Vec<_>>(),
+            vec![OpenParen, Identifier("a"), Identifier("b")]
+        );
+        assert_eq!(
+            lexed.errors,
+            vec![
+                (Span::new(11, 17, None), TokenError::InvalidCharacter),
+                (
+                    Span::new(20, 33, None),
+                    TokenError::IncompleteString {
+                        partial: "unterminated".to_string(),
+                        in_escape: false
+                    }
+                ),
+            ]
+        );
+
+        assert_eq!(lex("(+ 1 2)").errors, vec![]);
+    }
+
+    #[test]
+    fn test_comment_levels() {
+        let levels: Vec<_> = Lexer::new(";;; file\n;; section\n(a) ; inline\n;;;; banner")
+            .filter_map(|t| match t {
+                Ok(Comment(level)) => Some(level),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            levels,
+            vec![
+                CommentLevel::File,
+                CommentLevel::Section,
+                CommentLevel::Inline,
+                CommentLevel::File,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_token_categories() {
+        use crate::tokens::TokenCategory;
+
+        let categories: Vec<_> = TokenStream::new(
+            "(define x #(#t 1.5 \"s\" #\\a #:key 'y ...)) ; done",
+            false,
+            None,
+        )
+        .map(|t| (t.source, t.category()))
+        .collect();
+
+        assert_eq!(
+            categories,
+            vec![
+                ("(", TokenCategory::Delimiter),
+                ("define", TokenCategory::Keyword),
+                ("x", TokenCategory::Identifier),
+                ("#(", TokenCategory::Delimiter),
+                ("#t", TokenCategory::Boolean),
+                ("1.5", TokenCategory::Number),
+                ("\"s\"", TokenCategory::String),
+                ("#\\a", TokenCategory::Character),
+                ("#:key", TokenCategory::Keyword),
+                ("'", TokenCategory::Operator),
+                ("y", TokenCategory::Identifier),
+                ("...", TokenCategory::Operator),
+                (")", TokenCategory::Delimiter),
+                (")", TokenCategory::Delimiter),
+                ("; done", TokenCategory::Comment),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trivia_round_trips() {
+        let input = ";;; header\n(define (f x)  ; inline\n\t(+ x 1))\n\n'(a b) ;; end";
+
+        let tokens: Vec<_> =
+            TokenStream::from_lexer(Lexer::new(input).with_trivia(), false, None).collect();
+
+        assert_eq!(tokens.iter().map(|t| t.source()).collect::<String>(), input);
+        assert_eq!(tokens[1].ty, Whitespace);
+        assert_eq!(tokens[1].source(), "\n");
+        assert!(tokens.iter().any(|t| t.source() == "  "));
+
+        // Without trivia, whitespace is still skipped.
+        assert!(TokenStream::new(input, false, None).all(|t| t.ty != Whitespace));
+    }
+
+    #[test]
+    fn test_rationals_are_reduced() {
+        let got: Vec<_> = TokenStream::new("6/8 -1/2 4/2 (/ 3 4)", true, None)
+            .map(|t| t.ty)
+            .collect();
+
+        let expected: Vec<TokenType<&str>> = vec![
+            RealLiteral::Rational(IntLiteral::Small(3), IntLiteral::Small(4)).into(),
+            RealLiteral::Rational(IntLiteral::Small(-1), IntLiteral::Small(2)).into(),
+            IntLiteral::Small(2).into(),
+            OpenParen,
+            Identifier("/"),
+            IntLiteral::Small(3).into(),
+            IntLiteral::Small(4).into(),
+            CloseParen,
+        ];
+
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_rational_zero_denominator() {
+        let got: Vec<_> = Lexer::new("1/0 (-3/0) 1/").collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Err(TokenError::InvalidNumber),
+                Ok(OpenParen),
+                Err(TokenError::InvalidNumber),
+                Ok(CloseParen),
+                Ok(Identifier("1/")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_boolean_spellings() {
+        let got: Vec<_> = Lexer::new("(not #t) (#f)#true #false").collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Ok(OpenParen),
+                Ok(Identifier("not")),
+                Ok(BooleanLiteral(true)),
+                Ok(CloseParen),
+                Ok(OpenParen),
+                Ok(BooleanLiteral(false)),
+                Ok(CloseParen),
+                Ok(BooleanLiteral(true)),
+                Ok(BooleanLiteral(false)),
+            ]
+        );
+
+        for word in ["#truex", "#tt", "#fa", "#falsey"] {
+            assert_eq!(
+                Lexer::new(word).next(),
+                Some(Err(TokenError::InvalidHashSyntax(word.to_string())))
+            );
+        }
+    }
+
+    #[test]
+    fn test_words_end_at_delimiters() {
+        let got: Vec<_> = Lexer::new("foo\"bar\" x;c\na'b 1\"s\" #t;c\n#'x")
+            .filter_map(|t| t.ok())
+            .collect();
+
+        let expected: Vec<TokenType<&str>> = vec![
+            Identifier("foo"),
+            StringLiteral("bar".to_string()),
+            Identifier("x"),
+            Comment(CommentLevel::Inline),
+            Identifier("a"),
+            QuoteTick,
+            Identifier("b"),
+            IntLiteral::Small(1).into(),
+            StringLiteral("s".to_string()),
+            BooleanLiteral(true),
+            Comment(CommentLevel::Inline),
+            QuoteSyntax,
+            Identifier("x"),
+        ];
+
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_comma_separators() {
+        let got: Vec<_> = Lexer::new("(a, b ,@c)").with_comma_separators().collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Ok(OpenParen),
+                Ok(Identifier("a")),
+                Ok(Comma),
+                Ok(Identifier("b")),
+                Ok(Comma),
+                Ok(Identifier("@c")),
+                Ok(CloseParen),
+            ]
+        );
+
+        let got: Vec<_> = Lexer::new("(a, b)").collect();
+        assert_eq!(got[2], Ok(Unquote));
+    }
+
+    #[test]
+    fn test_signs_and_negative_numbers() {
+        fn lex(input: &str) -> Vec<TokenType<&str>> {
+            Lexer::new(input).map(|t| t.unwrap()).collect()
+        }
+
+        assert_eq!(
+            lex("(- 1)"),
+            vec![
+                OpenParen,
+                Identifier("-"),
+                IntLiteral::Small(1).into(),
+                CloseParen
+            ]
+        );
+        assert_eq!(
+            lex("(- 1 2)"),
+            vec![
+                OpenParen,
+                Identifier("-"),
+                IntLiteral::Small(1).into(),
+                IntLiteral::Small(2).into(),
+                CloseParen
+            ]
+        );
+        assert_eq!(
+            lex("(f -1)"),
+            vec![
+                OpenParen,
+                Identifier("f"),
+                IntLiteral::Small(-1).into(),
+                CloseParen
+            ]
+        );
+        assert_eq!(
+            lex("(-1 +2)"),
+            vec![
+                OpenParen,
+                IntLiteral::Small(-1).into(),
+                IntLiteral::Small(2).into(),
+                CloseParen
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identifier_occurrences() {
+        let occurrences = TokenStream::new("(define (f x) (+ x 1)) ; x\n(f (f x))", true, None)
+            .identifier_occurrences();
+
+        assert_eq!(occurrences.len(), 3);
+        assert_eq!(
+            occurrences["f"],
+            vec![
+                Span::new(9, 10, None),
+                Span::new(28, 29, None),
+                Span::new(31, 32, None),
+            ]
+        );
+        assert_eq!(
+            occurrences["x"],
+            vec![
+                Span::new(11, 12, None),
+                Span::new(17, 18, None),
+                Span::new(33, 34, None),
+            ]
+        );
+        assert_eq!(occurrences["+"], vec![Span::new(15, 16, None)]);
+    }
+
+    #[test]
+    fn test_forty_digit_integer_is_exact() {
+        let digits = "1234567890123456789012345678901234567890";
+        let mut lexer = Lexer::new(digits);
+
+        assert_eq!(
+            lexer.next(),
+            Some(Ok(IntLiteral::Big(Box::new(digits.parse().unwrap())).into()))
+        );
+        assert_eq!(
+            Lexer::new("-1234567890123456789012345678901234567890").next(),
+            Some(Ok(IntLiteral::Big(Box::new(
+                format!("-{digits}").parse().unwrap()
+            ))
+            .into()))
+        );
+    }
+
+    #[test]
+    fn test_remaining() {
+        let mut stream = TokenStream::new("(define <<EOF\nraw text\nEOF", true, None);
+
+        assert_eq!(stream.remaining(), "(define <<EOF\nraw text\nEOF");
+        assert_eq!(stream.next().map(|t| t.ty), Some(OpenParen));
+        assert_eq!(stream.next().map(|t| t.ty), Some(Define));
+        assert_eq!(stream.remaining(), " <<EOF\nraw text\nEOF");
+
+        stream.by_ref().for_each(drop);
+        assert_eq!(stream.remaining(), "");
+    }
+
+    #[test]
+    fn test_string_hex_escapes() {
+        let got: Vec<_> =
+            Lexer::new(r#""\x41;\x3bb; \x41;" "\x41" "\xD800;" "\x110000;" "ok""#).collect();
+
+        assert_eq!(
+            got,
+            vec![
+                Ok(StringLiteral("Aλ A".to_string())),
+                Ok(StringLiteral("A".to_string())),
+                Err(TokenError::InvalidEscape),
+                Err(TokenError::InvalidEscape),
+                Ok(StringLiteral("ok".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_iterate_without_consuming() {
+        let mut stream = TokenStream::new("(a (b) c) rest", true, None);
+        let mut sources = Vec::new();
+
+        for token in &mut stream {
+            sources.push(token.source());
+
+            if token.source() == "b" {
+                break;
+            }
+        }
+
+        assert_eq!(sources, vec!["(", "a", "(", "b"]);
+        assert_eq!(stream.depth(), 2);
+        assert_eq!(stream.remaining(), ") c) rest");
+    }
+
+    #[test]
+    fn test_incomplete_string_keeps_contents() {
+        assert_eq!(
+            Lexer::new(r#""abc"#).next(),
+            Some(Err(TokenError::IncompleteString {
+                partial: "abc".to_string(),
+                in_escape: false
+            }))
+        );
+        assert_eq!(
+            Lexer::new(r#""a\tb\"#).next(),
+            Some(Err(TokenError::IncompleteString {
+                partial: "a\tb".to_string(),
+                in_escape: true
+            }))
+        );
+    }
+
+    #[test]
+    fn test_category_counts() {
+        let counts = TokenStream::new(
+            "(define (greet name) ; says hi\n  (display \"hi \" name 42 #\\!))",
+            false,
+            None,
+        )
+        .category_counts();
+
+        assert_eq!(
+            counts,
+            BTreeMap::from([
+                (TokenCategory::Delimiter, 6),
+                (TokenCategory::Keyword, 1),
+                (TokenCategory::Identifier, 4),
+                (TokenCategory::Comment, 1),
+                (TokenCategory::String, 1),
+                (TokenCategory::Number, 1),
+                (TokenCategory::Character, 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tab_width() {
+        let input = "\t(foo\n\t\tbar)\nab\tc";
+        let bar = input.find("bar").unwrap();
+        let c = input.rfind('c').unwrap();
+
+        let lexer = Lexer::new(input);
+        assert_eq!(lexer.line_column(2), (1, 3));
+        assert_eq!(lexer.line_column(bar), (2, 3));
+
+        let lexer = Lexer::new(input).with_tab_width(4);
+        assert_eq!(lexer.line_column(2), (1, 6));
+        assert_eq!(lexer.line_column(bar), (2, 9));
+        assert_eq!(lexer.line_column(c), (3, 5));
+    }
+
+    #[test]
+    fn test_streaming_lexer() {
+        let mut lexer = StreamingLexer::new();
+
+        lexer.feed("(+ 1 ");
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(OpenParen, Span::new(0, 1, None)))
+        );
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                Identifier("+".to_string()),
+                Span::new(1, 2, None)
+            ))
+        );
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                IntLiteral::Small(1).into(),
+                Span::new(3, 4, None)
+            ))
+        );
+        assert_eq!(lexer.next_token(), Some(LexPoll::Pending));
+        assert_eq!(lexer.depth(), 1);
+
+        lexer.feed("2)");
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                IntLiteral::Small(2).into(),
+                Span::new(5, 6, None)
+            ))
+        );
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(CloseParen, Span::new(6, 7, None)))
+        );
+        assert_eq!(lexer.next_token(), Some(LexPoll::Pending));
+        assert_eq!(lexer.depth(), 0);
+
+        lexer.finish();
+        assert_eq!(lexer.next_token(), None);
+    }
+
+    #[test]
+    fn test_streaming_lexer_resumes_tokens() {
+        let mut lexer = StreamingLexer::new();
+
+        lexer.feed("\"hel");
+        assert_eq!(lexer.next_token(), Some(LexPoll::Pending));
+        lexer.feed("lo\" 12");
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                StringLiteral("hello".to_string()),
+                Span::new(0, 7, None)
+            ))
+        );
+        assert_eq!(lexer.next_token(), Some(LexPoll::Pending));
+
+        lexer.feed("34");
+        lexer.finish();
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                IntLiteral::Small(1234).into(),
+                Span::new(8, 12, None)
+            ))
+        );
+        assert_eq!(lexer.next_token(), None);
+    }
+
+    #[test]
+    fn test_streaming_lexer_string_across_chunks() {
+        let mut lexer = StreamingLexer::new();
+
+        for chunk in ["\"a\n", "b\n", "c\\", "\"\"", " d"] {
+            lexer.feed(chunk);
+        }
+        lexer.finish();
+
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                StringLiteral("a\nb\nc\"".to_string()),
+                Span::new(0, 9, None)
+            ))
+        );
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                Identifier("d".to_string()),
+                Span::new(10, 11, None)
+            ))
+        );
+        assert_eq!(lexer.next_token(), None);
+
+        let mut lexer = StreamingLexer::new();
+
+        lexer.feed("\"a");
+        assert_eq!(lexer.next_token(), Some(LexPoll::Pending));
+        lexer.feed("b");
+        assert_eq!(lexer.next_token(), Some(LexPoll::Pending));
+        lexer.feed("\"");
+        assert_eq!(
+            lexer.next_token(),
+            Some(LexPoll::Ready(
+                StringLiteral("ab".to_string()),
+                Span::new(0, 4, None)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_streaming_lexer_options() {
+        let mut lexer = StreamingLexer::with_lexer(Lexer::new("").with_curly_infix());
+
+        lexer.feed("{a}");
+        lexer.finish();
+
+        let got: Vec<_> = core::iter::from_fn(|| lexer.next_token()).collect();
+        assert_eq!(
+            got,
+            vec![
+                LexPoll::Ready(OpenCurly, Span::new(0, 1, None)),
+                LexPoll::Ready(Identifier("a".to_string()), Span::new(1, 2, None)),
+                LexPoll::Ready(CloseCurly, Span::new(2, 3, None)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_strings() {
+        let mut lexer = Lexer::new(r#"(display "abc"#).with_partial_strings();
+
+        assert_eq!(lexer.next(), Some(Ok(OpenParen)));
+        assert_eq!(lexer.next(), Some(Ok(Identifier("display"))));
+        assert_eq!(lexer.next(), Some(Ok(PartialString("abc".to_string()))));
+        assert_eq!(lexer.next(), None);
+
+        assert_eq!(
+            lexer.diagnostics(),
+            &[(
+                Span::new(9, 13, None),
+                TokenError::IncompleteString {
+                    partial: "abc".to_string(),
+                    in_escape: false,
+                }
+            )]
+        );
+        assert_eq!(
+            PartialString::<&str>("abc".to_string()).to_string(),
+            "\"abc"
+        );
+
+        // Without the option it's still an error
+        let mut lexer = Lexer::new(r#""abc"#);
+        assert!(matches!(
+            lexer.next(),
+            Some(Err(TokenError::IncompleteString { .. }))
+        ));
+        assert!(lexer.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_arrows() {
+        let tokens = |lexer: Lexer<'static>| lexer.collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            tokens(Lexer::new("(cond (a => f) (else b))").with_arrows()),
+            vec![
+                OpenParen,
+                Identifier("cond"),
+                OpenParen,
+                Identifier("a"),
+                Arrow,
+                Identifier("f"),
+                CloseParen,
+                OpenParen,
+                Identifier("else"),
+                Identifier("b"),
+                CloseParen,
+                CloseParen,
+            ]
+        );
+
+        // Only exactly `=>` is an arrow
+        assert_eq!(
+            tokens(Lexer::new("= > =>> ==>").with_arrows()),
+            vec![
+                Identifier("="),
+                Identifier(">"),
+                Identifier("=>>"),
+                Identifier("==>"),
+            ]
+        );
+
+        assert_eq!(tokens(Lexer::new("=>")), vec![Identifier("=>")]);
+    }
+
+    #[test]
+    fn test_token_buffer_cursor() {
+        let mut buffer = TokenBuffer::new("(f x) ; done\n y");
+
+        assert_eq!(buffer.len(), 5);
+        assert_eq!(buffer.peek_kind(), Some(&OpenParen));
+        assert_eq!(buffer.advance().map(|t| t.source), Some("("));
+        assert_eq!(buffer.peek_kind(), Some(&Identifier("f")));
+
+        let saved = buffer.position();
+        assert_eq!(buffer.advance().map(|t| t.source), Some("f"));
+        assert_eq!(buffer.advance().map(|t| t.source), Some("x"));
+        assert_eq!(buffer.position(), 3);
+
+        buffer.backtrack(saved);
+        assert_eq!(buffer.peek_kind(), Some(&Identifier("f")));
+
+        let sources: Vec<_> = buffer.slice(1..3).iter().map(|t| t.source).collect();
+        assert_eq!(sources, ["f", "x"]);
+        assert_eq!(buffer.get(4).map(|t| t.span), Some(Span::new(14, 15, None)));
+
+        while buffer.advance().is_some() {}
+        assert!(buffer.is_at_end());
+        assert_eq!(buffer.peek_kind(), None);
+        assert!(buffer.errors().is_empty());
+
+        buffer.backtrack(0);
+        assert_eq!(buffer.peek_kind(), Some(&OpenParen));
+    }
+
+    #[test]
+    fn test_token_buffer_keeps_errors() {
+        let buffer = TokenBuffer::new("(a #\\bogus b)");
+
+        assert_eq!(
+            buffer.errors(),
+            &[(Span::new(3, 10, None), TokenError::InvalidCharacter)]
+        );
+        assert_eq!(
+            buffer
+                .slice(0..buffer.len())
+                .iter()
+                .map(|t| t.source)
+                .collect::<Vec<_>>(),
+            ["(", "a", "b", ")"]
+        );
+    }
+
+    #[test]
+    fn test_zero_based_positions() {
+        let input = "(define x\n  (foo))";
+        let foo = input.find("foo").unwrap();
+
+        assert_eq!(Lexer::new(input).line_column(0), (1, 1));
+        assert_eq!(Lexer::new(input).line_column(foo), (2, 4));
+
+        let lexer = Lexer::new(input).with_zero_based_positions();
+        assert_eq!(lexer.line_column(0), (0, 0));
+        assert_eq!(lexer.line_column(foo), (1, 3));
+    }
+
+    #[test]
+    fn test_comment_char() {
+        let tokens = |lexer: Lexer<'static>| lexer.collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            tokens(Lexer::new("# a comment\n(port 80) ## another").with_comment_char(Some('#'))),
+            vec![
+                Comment(CommentLevel::Inline),
+                OpenParen,
+                Identifier("port"),
+                IntLiteral::Small(80).into(),
+                CloseParen,
+                Comment(CommentLevel::Section),
+            ]
+        );
+
+        // `;` is an ordinary character once it doesn't start comments
+        assert_eq!(
+            tokens(Lexer::new("a;b ; c").with_comment_char(Some('#'))),
+            vec![Identifier("a;b"), Identifier(";"), Identifier("c")]
+        );
+        assert_eq!(
+            tokens(Lexer::new("a;b").with_comment_char(None)),
+            vec![Identifier("a;b")]
+        );
+    }
+
+    #[test]
+    fn test_control_characters() {
+        let mut lexer = Lexer::new("(foo\x07 bar)");
+        assert_eq!(lexer.next(), Some(Ok(OpenParen)));
+        assert_eq!(lexer.next(), Some(Ok(Identifier("foo"))));
+        assert_eq!(
+            lexer.next(),
+            Some(Err(TokenError::ControlCharacter('\x07')))
+        );
+        assert_eq!(lexer.span(), 4..5);
+        assert_eq!(lexer.next(), Some(Ok(Identifier("bar"))));
+        assert_eq!(lexer.next(), Some(Ok(CloseParen)));
+        assert_eq!(lexer.next(), None);
+
+        let lexed = lex("(display \"ring\x07\")\x07");
+        assert_eq!(
+            lexed.errors,
+            [(
+                Span::new(16, 17, None),
+                TokenError::ControlCharacter('\x07')
+            )]
+        );
+        assert_eq!(lexed.tokens.len(), 4);
+    }
+
+    #[test]
+    fn test_bar_identifier_escapes() {
+        let identifiers = |input: &str| {
+            TokenStream::new(input, true, None)
+                .into_owned(OwnedString)
+                .map(|token| token.ty)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            identifiers(r"|a\x41;b|"),
+            vec![Identifier("aAb".to_string())]
+        );
+        assert_eq!(
+            identifiers(r#"|tab\there| |\|\\\"| |a b| ||"#),
+            vec![
+                Identifier("tab\there".to_string()),
+                Identifier("|\\\"".to_string()),
+                Identifier("a b".to_string()),
+                Identifier("".to_string()),
+            ]
+        );
+        // Bars keep the case as written
+        assert_eq!(
+            identifiers("#!fold-case |Foo| Bar"),
+            vec![Identifier("Foo".to_string()), Identifier("bar".to_string())]
+        );
+
+        let mut lexer = Lexer::new(r"(|a\qb| c)");
+        assert_eq!(lexer.next(), Some(Ok(OpenParen)));
+        assert_eq!(lexer.next(), Some(Err(TokenError::InvalidEscape)));
+        assert_eq!(lexer.span(), 1..7);
+        assert_eq!(lexer.next(), Some(Ok(Identifier("c"))));
+
+        assert_eq!(
+            Lexer::new(r"|a\x41b|").next(),
+            Some(Err(TokenError::InvalidEscape))
+        );
+        assert_eq!(
+            Lexer::new(r"|a\x110000;|").next(),
+            Some(Err(TokenError::InvalidEscape))
+        );
+        assert_eq!(
+            Lexer::new("|abc").next(),
+            Some(Err(TokenError::IncompleteIdentifier))
+        );
+    }
+
+    #[test]
+    fn test_c_radix_prefixes() {
+        let number = |input: &str| Lexer::new(input).with_c_radix_prefixes().next().unwrap();
+
+        assert_eq!(number("0xFF"), Ok(IntLiteral::Small(255).into()));
+        assert_eq!(number("0b101"), Ok(IntLiteral::Small(5).into()));
+        assert_eq!(number("0o17"), Ok(IntLiteral::Small(15).into()));
+        assert_eq!(number("-0x10"), Ok(IntLiteral::Small(-16).into()));
+        // A leading zero alone still means decimal
+        assert_eq!(number("012"), Ok(IntLiteral::Small(12).into()));
+
+        assert_eq!(number("0xFG"), Err(TokenError::MalformedHexInteger));
+        assert_eq!(number("0b102"), Err(TokenError::MalformedBinaryInteger));
+        assert_eq!(number("0x"), Err(TokenError::MalformedHexInteger));
+
+        assert_eq!(
+            Lexer::new("(f 0x1f)")
+                .with_c_radix_prefixes()
+                .collect::<Vec<_>>(),
+            [
+                Ok(OpenParen),
+                Ok(Identifier("f")),
+                Ok(IntLiteral::Small(31).into()),
+                Ok(CloseParen),
+            ]
+        );
+
+        // Off by default
+        assert_eq!(
+            Lexer::new("012").next(),
+            Some(Ok(IntLiteral::Small(12).into()))
+        );
+        assert_eq!(Lexer::new("0xFF").next(), Some(Ok(Identifier("0xFF"))));
+    }
+
+    #[test]
+    fn test_normalize_booleans() {
+        let input = "#true #t #false #f";
+
+        assert_eq!(
+            Lexer::new(input).map(Result::unwrap).collect::<Vec<_>>(),
+            [
+                BooleanLiteral(true),
+                BooleanLiteral(true),
+                BooleanLiteral(false),
+                BooleanLiteral(false),
+            ]
+        );
+
+        let spelled = Lexer::new(input).with_normalize_booleans(false);
+        assert_eq!(
+            spelled.map(Result::unwrap).collect::<Vec<_>>(),
+            [
+                BooleanLiteral(true),
+                ShortBooleanLiteral(true),
+                BooleanLiteral(false),
+                ShortBooleanLiteral(false),
+            ]
+        );
+
+        // Only without normalizing, tokens display the way they were written
+        let display = |lexer: Lexer<'_>| {
+            lexer
+                .map(|token| token.unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            display(Lexer::new(input)),
+            ["#true", "#true", "#false", "#false"]
+        );
+        assert_eq!(
+            display(Lexer::new(input).with_normalize_booleans(false)),
+            ["#true", "#t", "#false", "#f"]
+        );
+    }
+
+    #[test]
+    fn test_leading_bom() {
+        let input = "\u{FEFF}(+ 1 2)";
+        let tokens = TokenStream::new(input, true, None).collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens.iter().map(|token| &token.ty).collect::<Vec<_>>(),
+            [
+                &OpenParen,
+                &Identifier("+"),
+                &IntLiteral::Small(1).into(),
+                &IntLiteral::Small(2).into(),
+                &CloseParen,
+            ]
+        );
+        // Spans are still offsets into the input with the BOM
+        assert_eq!(tokens[0].span, Span::new(3, 4, None));
+        assert_eq!(Lexer::new(input).line_column(3), (1, 1));
+
+        let mut lexer = Lexer::new("(a \u{FEFF})");
+        assert_eq!(lexer.next(), Some(Ok(OpenParen)));
+        assert_eq!(lexer.next(), Some(Ok(Identifier("a"))));
+        assert_eq!(
+            lexer.next(),
+            Some(Err(TokenError::UnexpectedChar('\u{FEFF}')))
+        );
+        assert_eq!(lexer.next(), Some(Ok(CloseParen)));
+    }
+
+    #[test]
+    fn test_source_map() {
+        let input = "(define (f x) ; double it\n  (* x 2))";
+        let mapped = tokenize_with_source_map(input).unwrap();
+
+        assert_eq!(mapped.tokens.len(), mapped.spans.len());
+        for (ty, span) in mapped.tokens.iter().zip(&mapped.spans) {
+            assert_eq!(ty.to_string(), input[span.range()]);
+        }
+
+        assert_eq!(
+            mapped.tokens,
+            vec![
+                OpenParen,
+                Define,
+                OpenParen,
+                Identifier("f"),
+                Identifier("x"),
+                CloseParen,
+                OpenParen,
+                Identifier("*"),
+                Identifier("x"),
+                IntLiteral::Small(2).into(),
+                CloseParen,
+                CloseParen,
+            ]
+        );
+        assert_eq!(mapped.span_of(7), Some(Span::new(29, 30, None)));
+        assert_eq!(mapped.span_of(12), None);
+    }
 }
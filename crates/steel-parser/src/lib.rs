@@ -1,6 +1,21 @@
+//! The lexer and parser for Steel.
+//!
+//! Without the default `std` feature, only the lexer is built: [`lexer`], [`tokens`],
+//! [`span`], [`diagnostics`] and [`minify`] stick to `core` and `alloc`. The parser and the
+//! interner need `std`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod ast;
+pub mod diagnostics;
+#[cfg(feature = "std")]
 pub mod interner;
 pub mod lexer;
+pub mod minify;
+#[cfg(feature = "std")]
 pub mod parser;
 pub mod span;
 pub mod tokens;
@@ -0,0 +1,159 @@
+//! Compacting source by writing its tokens back out with as little whitespace as possible.
+
+use alloc::string::String;
+
+use crate::lexer::{is_delimiter, Lexer, Result};
+use crate::tokens::TokenType;
+
+/// Drops the comments and whitespace from `input`, keeping a single space only between
+/// tokens that would otherwise run together, like two identifiers or a number and an
+/// identifier. A datum comment is dropped along with the datum it comments out. Tokens are
+/// copied the way they were written, so strings keep their escapes.
+pub fn minify(input: &str) -> Result<String> {
+    let mut lexer = Lexer::new(input);
+    let mut output = String::with_capacity(input.len());
+    let mut previous: Option<&str> = None;
+    // The end of the input copied so far. The lexer reads `+x` as `+` and a queued `x`,
+    // which both have the span of `+x`, so the second one has already been written out.
+    let mut copied = 0;
+    // The datums still to drop for the datum comments seen, and how deep into one we are
+    let mut dropping = 0usize;
+    let mut depth = 0usize;
+
+    while let Some(token) = lexer.next() {
+        let token = token?;
+        let span = lexer.span();
+
+        if let TokenType::Comment(_) = token {
+            continue;
+        }
+
+        if span.end <= copied {
+            continue;
+        }
+
+        if let TokenType::DatumComment = token {
+            if depth == 0 {
+                dropping += 1;
+            }
+            copied = span.end;
+            continue;
+        }
+
+        if dropping > 0 {
+            if token.is_open_delimiter() {
+                depth += 1;
+            } else if token.is_close_delimiter() {
+                // A datum comment without a datum, as in `(a #;)`, is left for the parser
+                // to report
+                if depth == 0 {
+                    dropping = 0;
+                } else {
+                    depth -= 1;
+                }
+            }
+
+            if dropping > 0 {
+                if depth == 0 && !token.is_reader_prefix() {
+                    dropping -= 1;
+                }
+                copied = span.end;
+                continue;
+            }
+        }
+
+        let source = &input[span.clone()];
+        if previous.map_or(false, |previous| needs_space(previous, source)) {
+            output.push(' ');
+        }
+        output.push_str(source);
+        previous = Some(source);
+        copied = span.end;
+    }
+
+    Ok(output)
+}
+
+/// Whether `next` would be read as part of `previous` when written right after it.
+fn needs_space(previous: &str, next: &str) -> bool {
+    // `, @x` unquotes the identifier `@x`, but `,@x` splices `x`
+    if previous.ends_with(',') && next.starts_with('@') {
+        return true;
+    }
+
+    // A character literal can end in a delimiter without being ended by it, as in `#\(`
+    let ends_itself = (previous.ends_with(is_delimiter) && !previous.starts_with("#\\"))
+        || previous.ends_with(",@");
+
+    !ends_itself && !next.starts_with(is_delimiter)
+}
+
+#[cfg(test)]
+mod minify_tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    fn kinds(input: &str) -> Vec<TokenType<&str>> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.ty)
+            .collect()
+    }
+
+    #[test]
+    fn test_minify() {
+        let input = r#"
+;; adds one
+(define (add1 x) ; inline
+  (+ x 1))
+#;(ignored form)
+(display '(a "b c" #\space ,@rest `(,x)))
+"#;
+        let minified = minify(input).unwrap();
+
+        assert_eq!(
+            minified,
+            r##"(define(add1 x)(+ x 1))(display'(a"b c"#\space,@rest`(,x)))"##
+        );
+        assert_eq!(
+            kinds(&minified),
+            kinds(r#"(define (add1 x) (+ x 1)) (display '(a "b c" #\space ,@rest `(,x)))"#)
+        );
+    }
+
+    #[test]
+    fn test_datum_comments_dropped() {
+        for (input, expected) in [
+            ("(a #;b c)", "(a c)"),
+            ("(a #; #;b 'c d)", "(a d)"),
+            ("(list #;(a #;b c) 1)", "(list 1)"),
+            ("#;[x] y", "y"),
+        ] {
+            assert_eq!(minify(input).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_tokens_kept_apart() {
+        for input in [
+            "(a b)",
+            "(f 1 x -1 - 1)",
+            "(#\\( a #\\\" b)",
+            "(, @x)",
+            "(1.5 #t #f |a b| c)",
+            "(+x 1)",
+            "(+ +x +)",
+        ] {
+            let minified = minify(input).unwrap();
+
+            assert_eq!(minified, input);
+            assert_eq!(kinds(&minified), kinds(input));
+        }
+    }
+
+    #[test]
+    fn test_minify_errors() {
+        assert!(minify("(a \"unterminated").is_err());
+    }
+}
@@ -6,9 +6,9 @@ use crate::{
     ast::{
         self, parse_begin, parse_define, parse_if, parse_lambda, parse_let, parse_new_let,
         parse_require, parse_set, parse_single_argument, Atom, ExprKind, List, Macro, PatternPair,
-        SyntaxRules, BEGIN, DEFINE, IF, LAMBDA, LAMBDA_FN, LAMBDA_SYMBOL, LET, PLAIN_LET,
-        QUASIQUOTE, QUOTE, RAW_UNQUOTE, RAW_UNQUOTE_SPLICING, REQUIRE, RETURN, SET, UNQUOTE,
-        UNQUOTE_SPLICING,
+        SyntaxRules, VectorKind, BEGIN, BYTEVECTOR, DEFINE, DOT, IF, LAMBDA, LAMBDA_FN,
+        LAMBDA_SYMBOL, LET, PLAIN_LET, QUASIQUOTE, QUOTE, RAW_UNQUOTE, RAW_UNQUOTE_SPLICING,
+        REQUIRE, RETURN, SET, UNQUOTE, UNQUOTE_SPLICING, VECTOR,
     },
     interner::InternedString,
     lexer::{OwnedTokenStream, ToOwnedString, TokenStream},
@@ -16,11 +16,7 @@ use crate::{
     tokens::{Token, TokenType},
 };
 
-#[derive(
-    Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, Debug, Ord, PartialOrd,
-)]
-#[repr(C)]
-pub struct SourceId(pub usize);
+pub use crate::span::SourceId;
 
 // TODO: Fix the visibility here
 pub static SYNTAX_OBJECT_ID: AtomicUsize = AtomicUsize::new(0);
@@ -172,6 +168,28 @@ pub enum ParseError {
     IncompleteString(String, Span, Option<Rc<PathBuf>>),
     SyntaxError(String, Span, Option<Rc<PathBuf>>),
     ArityMismatch(String, Span, Option<Rc<PathBuf>>),
+    /// A closing delimiter at the top level, with nothing open for it to close.
+    UnexpectedClose(TokenType<String>, Span, Option<Rc<PathBuf>>),
+    /// Input ended while the delimiter at `open_span` was still open.
+    MissingCloseParen {
+        open_span: Span,
+        suggestion: String,
+        source: Option<Rc<PathBuf>>,
+    },
+    /// `found` appeared where only one of `expected` would have been valid, e.g. a `}`
+    /// closing a list opened with `(`.
+    UnexpectedToken {
+        found: TokenType<String>,
+        span: Span,
+        expected: Vec<TokenType<String>>,
+        source: Option<Rc<PathBuf>>,
+    },
+    /// A `,@` with no list around it to splice into, like `,@x` at the top level or right
+    /// after a quasiquote in `` `,@x ``.
+    MisplacedSplice(Span, Option<Rc<PathBuf>>),
+    /// A `.` in a dotted list that isn't between the last two datums, like in `(a . b c)`,
+    /// `(a .)` or `(. a)`.
+    MisplacedDot(Span, Option<Rc<PathBuf>>),
 }
 
 impl std::fmt::Display for ParseError {
@@ -185,6 +203,33 @@ impl std::fmt::Display for ParseError {
             ParseError::IncompleteString(l, _, _) => write!(f, "Parse: Incomplete String: {}", l),
             ParseError::SyntaxError(l, _, _) => write!(f, "Parse: Syntax Error: {}", l),
             ParseError::ArityMismatch(l, _, _) => write!(f, "Parse: Arity mismatch: {}", l),
+            ParseError::UnexpectedClose(l, _, _) => {
+                write!(f, "Parse: Unexpected closing delimiter: {}", l)
+            }
+            ParseError::MissingCloseParen { suggestion, .. } => {
+                write!(f, "Parse: Unexpected EOF: {}", suggestion)
+            }
+            ParseError::UnexpectedToken {
+                found, expected, ..
+            } => {
+                write!(f, "Parse: Unexpected token: {}, expected ", found)?;
+                for (i, token) in expected.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " or ")?;
+                    }
+                    write!(f, "{}", token)?;
+                }
+                Ok(())
+            }
+            ParseError::MisplacedSplice(_, _) => {
+                write!(f, "Parse: ,@ can only be used inside of a list")
+            }
+            ParseError::MisplacedDot(_, _) => {
+                write!(
+                    f,
+                    "Parse: . has to be followed by exactly one datum at the end of a list"
+                )
+            }
         }
     }
 }
@@ -201,6 +246,11 @@ impl ParseError {
             ParseError::IncompleteString(_, s, _) => Some(*s),
             ParseError::SyntaxError(_, s, _) => Some(*s),
             ParseError::ArityMismatch(_, s, _) => Some(*s),
+            ParseError::UnexpectedClose(_, s, _) => Some(*s),
+            ParseError::MissingCloseParen { open_span, .. } => Some(*open_span),
+            ParseError::UnexpectedToken { span, .. } => Some(*span),
+            ParseError::MisplacedSplice(s, _) => Some(*s),
+            ParseError::MisplacedDot(s, _) => Some(*s),
         }
     }
 
@@ -213,6 +263,37 @@ impl ParseError {
             ParseError::IncompleteString(l, s, _) => IncompleteString(l, s, source),
             ParseError::SyntaxError(l, s, _) => SyntaxError(l, s, source),
             ParseError::ArityMismatch(l, s, _) => ArityMismatch(l, s, source),
+            ParseError::UnexpectedClose(l, s, _) => UnexpectedClose(l, s, source),
+            ParseError::MissingCloseParen {
+                open_span,
+                suggestion,
+                ..
+            } => MissingCloseParen {
+                open_span,
+                suggestion,
+                source,
+            },
+            ParseError::UnexpectedToken {
+                found,
+                span,
+                expected,
+                ..
+            } => UnexpectedToken {
+                found,
+                span,
+                expected,
+                source,
+            },
+            ParseError::MisplacedSplice(s, _) => MisplacedSplice(s, source),
+            ParseError::MisplacedDot(s, _) => MisplacedDot(s, source),
+        }
+    }
+
+    /// A human-readable hint for fixing the error, if one is known.
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            ParseError::MissingCloseParen { suggestion, .. } => Some(suggestion),
+            _ => None,
         }
     }
 }
@@ -220,7 +301,7 @@ impl ParseError {
 pub struct InternString;
 
 impl ToOwnedString<InternedString> for InternString {
-    fn own(&self, s: &str) -> InternedString {
+    fn own(&mut self, s: &str) -> InternedString {
         s.into()
     }
 }
@@ -237,6 +318,8 @@ pub struct Parser<'a> {
     comment_buffer: Vec<&'a str>,
     collecting_comments: bool,
     keep_lists: bool,
+    strict: bool,
+    dotted_lists: bool,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -259,11 +342,79 @@ enum ParsingContext {
     UnquoteSplicingTick(usize),
 }
 
+// The kind of delimiter that opened the list currently being read
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum FrameKind {
+    List,
+    CurlyInfix,
+    Vector,
+    Bytevector,
+}
+
+impl FrameKind {
+    fn close_token(self) -> TokenType<String> {
+        match self {
+            FrameKind::CurlyInfix => TokenType::CloseCurly,
+            _ => TokenType::CloseParen,
+        }
+    }
+
+    fn missing_close_suggestion(self) -> String {
+        match self {
+            FrameKind::List => "add ')' to close this list".to_string(),
+            FrameKind::CurlyInfix => "add '}' to close this curly-infix expression".to_string(),
+            FrameKind::Vector => "add ')' to close this vector".to_string(),
+            FrameKind::Bytevector => "add ')' to close this bytevector".to_string(),
+        }
+    }
+}
+
+enum CurlyInfix {
+    Expr(ExprKind),
+    List(Vec<ExprKind>),
+}
+
+fn mark_improper(expr: &mut ExprKind) {
+    if let ExprKind::List(l) = expr {
+        l.improper = true;
+    }
+}
+
+fn mark_vector(expr: &mut ExprKind, kind: VectorKind) {
+    if let ExprKind::List(l) = expr {
+        l.vector = Some(kind);
+    }
+}
+
+// Applies the SRFI-105 simple curly-infix rules to the contents of a `{...}` form:
+// `{}` is the empty list, `{e}` is just `e`, `{a b}` is `(a b)` and `{a op b op c}`,
+// with the same operator in every odd position, is `(op a b c)`. Anything else is
+// handed off to `$nfx$`.
+fn curly_infix(mut frame: Vec<ExprKind>) -> CurlyInfix {
+    match frame.len() {
+        0 | 2 => CurlyInfix::List(frame),
+        1 => CurlyInfix::Expr(frame.pop().unwrap()),
+        n if n % 2 == 1 && frame[1..].iter().step_by(2).all(|op| *op == frame[1]) => {
+            let mut expr = vec![frame[1].clone()];
+            expr.extend(frame.into_iter().step_by(2));
+            CurlyInfix::List(expr)
+        }
+        _ => {
+            frame.insert(0, ExprKind::ident("$nfx$"));
+            CurlyInfix::List(frame)
+        }
+    }
+}
+
 impl<'a> Parser<'a> {
+    /// Parses every top-level form in `expr`, failing with the first [`ParseError`]
+    /// encountered, e.g. [`ParseError::MissingCloseParen`] for an unclosed list, or
+    /// [`ParseError::UnexpectedClose`] for a stray close paren.
     pub fn parse(expr: &str) -> Result<Vec<ExprKind>> {
         Parser::new(expr, None).collect()
     }
 
+    /// Like [`Parser::parse`], but keeps special forms as plain lists.
     pub fn parse_without_lowering(expr: &str) -> Result<Vec<ExprKind>> {
         Parser::new(expr, None).without_lowering().collect()
     }
@@ -271,6 +422,87 @@ impl<'a> Parser<'a> {
     pub fn offset(&self) -> usize {
         self.tokenizer.offset()
     }
+
+    /// Reads the input as a single datum, failing with [`ParseError::UnexpectedEOF`] if
+    /// there is none. Another datum or a stray close paren after it is an error, unless
+    /// the parser is [`Parser::lenient`], which ignores whatever follows the datum.
+    pub fn read_single(mut self) -> Result<ExprKind> {
+        let datum = match self.next() {
+            Some(datum) => datum?,
+            None => return Err(ParseError::UnexpectedEOF(self.source_name.clone())),
+        };
+
+        if !self.strict {
+            return Ok(datum);
+        }
+
+        match self.skip_datum() {
+            Ok(span) => Err(ParseError::SyntaxError(
+                "expected a single datum, found another one after it".to_string(),
+                span,
+                self.source_name.clone(),
+            )),
+            // Only comments follow the datum
+            Err(ParseError::UnexpectedEOF(_)) => Ok(datum),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Consumes exactly one datum without building it, returning its span. Prefixes like
+    /// `'` are skipped along with the datum they apply to, and nested datum comments
+    /// count as usual, so `#; a b` is skipped as a whole. Used for `#;` datum comments.
+    pub fn skip_datum(&mut self) -> Result<Span> {
+        let mut start = None;
+        let mut depth = 0usize;
+        // The number of datums still to skip at the top level
+        let mut pending = 1usize;
+
+        while let Some(token) = self.tokenizer.next() {
+            match token.ty {
+                TokenType::Comment(_) | TokenType::Whitespace => continue,
+                TokenType::Error => return Err(tokentype_error_to_parse_error(&token)),
+                _ => {}
+            }
+
+            let start = *start.get_or_insert(token.span);
+
+            if token.ty.is_open_delimiter() {
+                depth += 1;
+            } else if token.ty.is_close_delimiter() {
+                if depth == 0 {
+                    let close = match token.ty {
+                        TokenType::CloseCurly => TokenType::CloseCurly,
+                        _ => TokenType::CloseParen,
+                    };
+
+                    return Err(ParseError::UnexpectedClose(
+                        close,
+                        token.span,
+                        self.source_name.clone(),
+                    ));
+                }
+
+                depth -= 1;
+            }
+
+            if depth > 0 || token.ty.is_reader_prefix() {
+                continue;
+            }
+
+            if let TokenType::DatumComment = token.ty {
+                pending += 1;
+                continue;
+            }
+
+            pending -= 1;
+
+            if pending == 0 {
+                return Ok(Span::merge(start, token.span));
+            }
+        }
+
+        Err(ParseError::UnexpectedEOF(self.source_name.clone()))
+    }
 }
 
 pub type Result<T> = result::Result<T, ParseError>;
@@ -314,6 +546,8 @@ impl<'a> Parser<'a> {
             comment_buffer: Vec::new(),
             collecting_comments: false,
             keep_lists: false,
+            strict: true,
+            dotted_lists: false,
         }
     }
 
@@ -322,6 +556,27 @@ impl<'a> Parser<'a> {
         self
     }
 
+    /// Skip over stray closing parens at the top level, and anything after the datum read
+    /// by [`Parser::read_single`], instead of reporting them as a [`ParseError`].
+    pub fn lenient(mut self) -> Self {
+        self.strict = false;
+        self
+    }
+
+    /// Read `{...}` using SRFI-105 curly-infix notation, e.g. `{a + b}` => `(+ a b)`.
+    pub fn with_curly_infix(mut self) -> Self {
+        self.tokenizer.lexer_mut().curly_infix = true;
+        self
+    }
+
+    /// Read `(a b . c)` as a list with `c` as its tail, marked as [`List::improper`],
+    /// instead of as a list containing the identifier `.`. Dotted parameter lists like
+    /// `(lambda (x . rest) ...)` become rest parameters.
+    pub fn with_dotted_lists(mut self) -> Self {
+        self.dotted_lists = true;
+        self
+    }
+
     pub fn new_flat(input: &'a str, source_id: Option<SourceId>) -> Self {
         let input = strip_shebang_line(input);
         Parser {
@@ -335,6 +590,8 @@ impl<'a> Parser<'a> {
             comment_buffer: Vec::new(),
             collecting_comments: false,
             keep_lists: true,
+            strict: true,
+            dotted_lists: false,
         }
     }
 
@@ -355,6 +612,8 @@ impl<'a> Parser<'a> {
             comment_buffer: Vec::new(),
             collecting_comments: false,
             keep_lists: false,
+            strict: true,
+            dotted_lists: false,
         }
     }
 
@@ -372,6 +631,8 @@ impl<'a> Parser<'a> {
             comment_buffer: Vec::new(),
             collecting_comments: false,
             keep_lists: false,
+            strict: true,
+            dotted_lists: false,
         }
     }
 
@@ -456,6 +717,49 @@ impl<'a> Parser<'a> {
         ExprKind::List(List::new(vec![q, val]))
     }
 
+    // Reader macro for #(
+    // Turns the contents of a vector literal into a call to `constructor`
+    // with each of the (quoted) elements.
+    fn construct_vector(
+        &mut self,
+        constructor: InternedString,
+        values: Vec<ExprKind>,
+        span: Span,
+    ) -> Vec<ExprKind> {
+        let mut vector = Vec::with_capacity(values.len() + 1);
+
+        vector.push(ExprKind::Atom(Atom::new(SyntaxObject::new(
+            TokenType::Identifier(constructor),
+            span,
+        ))));
+        vector.extend(values.into_iter().map(|x| self.construct_quote(x, span)));
+
+        vector
+    }
+
+    // A vector literal directly inside a quasiquote, like `#(1 ,@xs)`, can't quote its
+    // elements, since they may be unquoted. Instead it is unquoted itself, to build the vector
+    // out of the quasiquoted list of its elements: `,(apply vector `(1 ,@xs))`.
+    fn construct_quasiquote_vector(&mut self, values: Vec<ExprKind>, span: Span) -> Vec<ExprKind> {
+        let ident = |name| {
+            ExprKind::Atom(Atom::new(SyntaxObject::new(
+                TokenType::Identifier(name),
+                span,
+            )))
+        };
+
+        let elements = self.construct_quasiquote(ExprKind::List(List::new(values)), span);
+
+        vec![
+            ident(*RAW_UNQUOTE),
+            ExprKind::List(List::new(vec![
+                ident("apply".into()),
+                ident(*VECTOR),
+                elements,
+            ])),
+        ]
+    }
+
     fn increment_quasiquote_context_if_not_in_quote_context(&mut self) {
         // println!("INCREMENTING");
         if !self.quote_context {
@@ -478,9 +782,46 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn read_from_tokens(&mut self) -> Result<ExprKind> {
+    // Removes the `.` from a list read with dotted lists enabled, returning whether there
+    // was one. It has to come after at least one datum and before exactly one.
+    fn take_dot(&self, frame: &mut Vec<ExprKind>) -> Result<bool> {
+        let mut dots = frame
+            .iter()
+            .enumerate()
+            .filter_map(|(index, expr)| match expr {
+                ExprKind::Atom(Atom {
+                    syn:
+                        SyntaxObject {
+                            ty: TokenType::Identifier(ident),
+                            span,
+                            ..
+                        },
+                }) if *ident == *DOT => Some((index, *span)),
+                _ => None,
+            });
+
+        let Some((index, span)) = dots.next() else {
+            return Ok(false);
+        };
+
+        if index == 0 || index + 2 != frame.len() {
+            return Err(ParseError::MisplacedDot(span, self.source_name.clone()));
+        }
+
+        if let Some((_, span)) = dots.next() {
+            return Err(ParseError::MisplacedDot(span, self.source_name.clone()));
+        }
+
+        frame.remove(index);
+
+        Ok(true)
+    }
+
+    fn read_from_tokens(&mut self, kind: FrameKind, open_span: Span) -> Result<ExprKind> {
         let mut stack: Vec<Vec<ExprKind>> = Vec::new();
         let mut current_frame: Vec<ExprKind> = Vec::new();
+        // Each open frame along with the span of the delimiter that opened it
+        let mut frame_kinds: Vec<(FrameKind, Span)> = vec![(kind, open_span)];
 
         self.quote_stack = Vec::new();
 
@@ -491,11 +832,15 @@ impl<'a> Parser<'a> {
             match self.tokenizer.next() {
                 Some(token) => {
                     match token.ty {
-                        TokenType::Comment => {
+                        TokenType::Comment(_) => {
                             // println!("Found a comment!");
                             // Internal comments, we're gonna skip for now
                             continue;
                         }
+                        TokenType::DatumComment => {
+                            self.skip_datum()?;
+                            continue;
+                        }
                         TokenType::Error => return Err(tokentype_error_to_parse_error(&token)), // TODO
                         TokenType::QuoteTick => {
                             // quote_count += 1;
@@ -638,11 +983,94 @@ impl<'a> Parser<'a> {
                             // println!("Exiting Context: {:?}", self.context.pop());
                             current_frame.push(quote_inner?);
                         }
-                        TokenType::OpenParen => {
+                        TokenType::OpenParen
+                        | TokenType::OpenCurly
+                        | TokenType::OpenVector
+                        | TokenType::OpenBytevector => {
                             stack.push(current_frame);
                             current_frame = Vec::new();
+
+                            let kind = match token.ty {
+                                TokenType::OpenCurly => FrameKind::CurlyInfix,
+                                TokenType::OpenVector => FrameKind::Vector,
+                                TokenType::OpenBytevector => FrameKind::Bytevector,
+                                _ => FrameKind::List,
+                            };
+
+                            frame_kinds.push((kind, token.span));
                         }
-                        TokenType::CloseParen => {
+                        TokenType::CloseParen | TokenType::CloseCurly => {
+                            let found = match token.ty {
+                                TokenType::CloseCurly => TokenType::CloseCurly,
+                                _ => TokenType::CloseParen,
+                            };
+
+                            let kind = frame_kinds.pop().map(|(kind, _)| kind);
+
+                            if let Some(kind) = kind {
+                                if kind.close_token() != found {
+                                    return Err(ParseError::UnexpectedToken {
+                                        found,
+                                        span: token.span,
+                                        expected: vec![kind.close_token()],
+                                        source: self.source_name.clone(),
+                                    });
+                                }
+                            }
+
+                            // In quoted data, a vector literal is a vector datum, rather than a
+                            // call that builds one
+                            let vector = match kind {
+                                Some(FrameKind::Vector)
+                                    if self.quote_context || self.quasiquote_depth > 1 =>
+                                {
+                                    Some(VectorKind::Vector)
+                                }
+                                Some(FrameKind::Bytevector)
+                                    if self.quote_context || self.quasiquote_depth > 0 =>
+                                {
+                                    Some(VectorKind::Bytevector)
+                                }
+                                _ => None,
+                            };
+
+                            match kind {
+                                _ if vector.is_some() => {}
+                                Some(FrameKind::CurlyInfix) => match curly_infix(current_frame) {
+                                    CurlyInfix::List(list) => current_frame = list,
+                                    CurlyInfix::Expr(expr) => match stack.pop() {
+                                        Some(mut prev_frame) => {
+                                            prev_frame.push(expr);
+                                            current_frame = prev_frame;
+                                            continue;
+                                        }
+                                        None => return Ok(expr),
+                                    },
+                                },
+                                Some(FrameKind::Vector)
+                                    if self.quasiquote_depth == 1 && !self.quote_context =>
+                                {
+                                    current_frame =
+                                        self.construct_quasiquote_vector(current_frame, token.span)
+                                }
+                                Some(FrameKind::Vector) => {
+                                    current_frame =
+                                        self.construct_vector(*VECTOR, current_frame, token.span)
+                                }
+                                Some(FrameKind::Bytevector) => {
+                                    current_frame = self.construct_vector(
+                                        *BYTEVECTOR,
+                                        current_frame,
+                                        token.span,
+                                    )
+                                }
+                                _ => {}
+                            }
+
+                            let improper = matches!(kind, Some(FrameKind::List))
+                                && self.dotted_lists
+                                && self.take_dot(&mut current_frame)?;
+
                             // This is the match that we'll want to move inside the below stack.pop() match statement
                             // As we close the current context, we check what our current state is -
 
@@ -812,6 +1240,14 @@ impl<'a> Parser<'a> {
 
                                 // Reinitialize current frame here
                                 current_frame = prev_frame;
+
+                                if let Some(expr) = current_frame.last_mut().filter(|_| improper) {
+                                    mark_improper(expr);
+                                }
+
+                                if let Some((expr, kind)) = current_frame.last_mut().zip(vector) {
+                                    mark_vector(expr, kind);
+                                }
                             } else {
                                 // println!("Else case: {:?}", current_frame);
                                 // println!("Context: {:?}", self.context);
@@ -819,53 +1255,58 @@ impl<'a> Parser<'a> {
                                 // dbg!(&self.quote_stack);
                                 // dbg!(&self.context);
                                 // dbg!(&self.shorthand_quote_stack);
-                                match self.context.last() {
+                                let expr = match self.context.last() {
                                     Some(ParsingContext::QuoteTick(_))
                                     | Some(ParsingContext::QuasiquoteTick(_)) => {
                                         // | Some(ParsingContext::Quote(d)) && d > 0 => {
 
-                                        return Ok(ExprKind::List(List::new(current_frame)));
+                                        Ok(ExprKind::List(List::new(current_frame)))
                                     }
                                     Some(ParsingContext::Quote(x)) if *x > 0 => {
                                         self.context.pop();
 
-                                        return Ok(ExprKind::List(List::new(current_frame)));
+                                        Ok(ExprKind::List(List::new(current_frame)))
                                     }
                                     Some(ParsingContext::Quote(0)) => {
                                         self.context.pop();
 
-                                        return self
-                                            .maybe_lower(current_frame)
-                                            .map_err(|x| x.set_source(self.source_name.clone()));
+                                        self.maybe_lower(current_frame)
+                                            .map_err(|x| x.set_source(self.source_name.clone()))
                                     }
                                     _ => {
                                         // dbg!(self.quasiquote_depth);
                                         // println!("=> {}", List::new(current_frame.clone()));
                                         // println!("----------------------------------------");
 
-                                        if self.quasiquote_depth > 0 {
-                                            // TODO/HACK - @Matt
-                                            // If we're in a define syntax situation, go ahead and just return a normal one
-                                            if current_frame
+                                        // TODO/HACK - @Matt
+                                        // If we're in a define syntax situation, go ahead and just return a normal one
+                                        if self.quasiquote_depth > 0
+                                            && !current_frame
                                                 .first()
                                                 .map(|x| x.define_syntax_ident())
                                                 .unwrap_or_default()
-                                            {
-                                                return self.maybe_lower(current_frame).map_err(
-                                                    |x| x.set_source(self.source_name.clone()),
-                                                );
-                                            }
-
+                                        {
                                             // println!("Should still be quoted here");
 
-                                            return Ok(ExprKind::List(List::new(current_frame)));
+                                            Ok(ExprKind::List(List::new(current_frame)))
+                                        } else {
+                                            self.maybe_lower(current_frame)
+                                                .map_err(|x| x.set_source(self.source_name.clone()))
                                         }
+                                    }
+                                };
 
-                                        return self
-                                            .maybe_lower(current_frame)
-                                            .map_err(|x| x.set_source(self.source_name.clone()));
+                                return expr.map(|mut expr| {
+                                    if improper {
+                                        mark_improper(&mut expr);
                                     }
-                                }
+
+                                    if let Some(kind) = vector {
+                                        mark_vector(&mut expr, kind);
+                                    }
+
+                                    expr
+                                });
                             }
                         }
 
@@ -925,7 +1366,16 @@ impl<'a> Parser<'a> {
                     }
                 }
 
-                None => return Err(ParseError::UnexpectedEOF(self.source_name.clone())),
+                None => {
+                    // Point at the innermost delimiter that was never closed
+                    let (kind, open_span) = *frame_kinds.last().unwrap();
+
+                    return Err(ParseError::MissingCloseParen {
+                        open_span,
+                        suggestion: kind.missing_close_suggestion(),
+                        source: self.source_name.clone(),
+                    });
+                }
             }
         }
     }
@@ -950,7 +1400,7 @@ impl<'a> Parser<'a> {
 
             if let Some(res) = next {
                 match res.ty {
-                    TokenType::Comment => {
+                    TokenType::Comment(_) => {
                         if self.comment_buffer.is_empty()
                             && !self.collecting_comments
                             && res.source().trim_start_matches(';').starts_with("@doc")
@@ -979,6 +1429,14 @@ impl<'a> Parser<'a> {
                         continue;
                     }
 
+                    TokenType::DatumComment => {
+                        if let Err(e) = self.skip_datum() {
+                            return Some(Err(e));
+                        }
+
+                        continue;
+                    }
+
                     TokenType::QuoteTick => {
                         // See if this does the job
                         self.shorthand_quote_stack.push(0);
@@ -1056,6 +1514,18 @@ impl<'a> Parser<'a> {
                     }
 
                     TokenType::UnquoteSplice => {
+                        // Inside of a list the splice is read by `read_from_tokens`, so here
+                        // it is either at the top level or right under a reader prefix.
+                        if matches!(
+                            self.context.last(),
+                            None | Some(ParsingContext::QuasiquoteTick(_))
+                        ) {
+                            return Some(Err(ParseError::MisplacedSplice(
+                                res.span,
+                                self.source_name.clone(),
+                            )));
+                        }
+
                         // println!("Entering Context: Unquotesplicing");
                         self.context.push(ParsingContext::UnquoteSplicingTick(0));
 
@@ -1107,16 +1577,34 @@ impl<'a> Parser<'a> {
                     }
 
                     TokenType::OpenParen => {
-                        let value = self.read_from_tokens();
+                        let value = self.read_from_tokens(FrameKind::List, res.span);
 
                         // self.quote_stack.clear();
                         // self.context.clear();
 
                         return Some(value);
                     }
+                    TokenType::OpenCurly => {
+                        return Some(self.read_from_tokens(FrameKind::CurlyInfix, res.span));
+                    }
+                    TokenType::OpenVector => {
+                        return Some(self.read_from_tokens(FrameKind::Vector, res.span));
+                    }
+                    TokenType::OpenBytevector => {
+                        return Some(self.read_from_tokens(FrameKind::Bytevector, res.span));
+                    }
+                    TokenType::CloseParen | TokenType::CloseCurly if !self.strict => continue,
                     TokenType::CloseParen => {
-                        return Some(Err(ParseError::Unexpected(
+                        return Some(Err(ParseError::UnexpectedClose(
                             TokenType::CloseParen,
+                            res.span,
+                            self.source_name.clone(),
+                        )))
+                    }
+                    TokenType::CloseCurly => {
+                        return Some(Err(ParseError::UnexpectedClose(
+                            TokenType::CloseCurly,
+                            res.span,
                             self.source_name.clone(),
                         )))
                     }
@@ -1484,6 +1972,7 @@ pub fn lower_entire_ast(expr: &mut ExprKind) -> Result<()> {
 mod parser_tests {
     // use super::TokenType::*;
     use super::*;
+    use crate::lexer::Lexer;
     use crate::parser::ast::{Begin, Define, If, LambdaFunction, Quote, Return};
     use crate::tokens::RealLiteral;
     use crate::{parser::ast::ExprKind, tokens::IntLiteral};
@@ -1686,16 +2175,32 @@ mod parser_tests {
         assert_parse("#\\(", &[character('(')])
     }
 
+    fn missing_close_paren(start: usize) -> ParseError {
+        ParseError::MissingCloseParen {
+            open_span: Span::new(start, start + 1, None),
+            suggestion: "add ')' to close this list".to_string(),
+            source: None,
+        }
+    }
+
+    fn unexpected_close(start: usize) -> ParseError {
+        ParseError::UnexpectedClose(
+            TokenType::CloseParen,
+            Span::new(start, start + 1, None),
+            None,
+        )
+    }
+
     #[test]
     fn test_error() {
-        assert_parse_err("(", ParseError::UnexpectedEOF(None));
-        assert_parse_err("(abc", ParseError::UnexpectedEOF(None));
-        assert_parse_err("(ab 1 2", ParseError::UnexpectedEOF(None));
-        assert_parse_err("((((ab 1 2) (", ParseError::UnexpectedEOF(None));
-        assert_parse_err("())", ParseError::Unexpected(TokenType::CloseParen, None));
-        assert_parse_err("() ((((", ParseError::UnexpectedEOF(None));
-        assert_parse_err("')", ParseError::Unexpected(TokenType::CloseParen, None));
-        assert_parse_err("(')", ParseError::Unexpected(TokenType::CloseParen, None));
+        assert_parse_err("(", missing_close_paren(0));
+        assert_parse_err("(abc", missing_close_paren(0));
+        assert_parse_err("(ab 1 2", missing_close_paren(0));
+        assert_parse_err("((((ab 1 2) (", missing_close_paren(12));
+        assert_parse_err("())", unexpected_close(2));
+        assert_parse_err("() ((((", missing_close_paren(6));
+        assert_parse_err("')", unexpected_close(1));
+        assert_parse_err("(')", unexpected_close(2));
         assert_parse_err("('", ParseError::UnexpectedEOF(None));
     }
 
@@ -1908,6 +2413,22 @@ mod parser_tests {
         )
     }
 
+    #[test]
+    fn test_quote_shorthand_twice() {
+        assert_parse(
+            "''x",
+            &[ExprKind::Quote(Box::new(Quote::new(
+                ExprKind::Quote(Box::new(Quote::new(
+                    atom("x"),
+                    SyntaxObject::default(TokenType::Quote),
+                ))),
+                SyntaxObject::default(TokenType::Quote),
+            )))],
+        );
+
+        assert_parse_err("(a ')", unexpected_close(4));
+    }
+
     #[test]
     fn test_quote_nested() {
         assert_parse(
@@ -2042,6 +2563,35 @@ mod parser_tests {
         )
     }
 
+    #[test]
+    fn test_nested_quasiquote_unquote_levels() {
+        // Only the unquote at the outermost level gets evaluated, the inner one
+        // belongs to the nested quasiquote and stays a plain `unquote`.
+        assert_parse(
+            "`(a ,(f) `(b ,(g)))",
+            &[ExprKind::List(List::new(vec![
+                atom("quasiquote"),
+                ExprKind::List(List::new(vec![
+                    atom("a"),
+                    ExprKind::List(List::new(vec![
+                        atom("#%unquote"),
+                        ExprKind::List(List::new(vec![atom("f")])),
+                    ])),
+                    ExprKind::List(List::new(vec![
+                        atom("quasiquote"),
+                        ExprKind::List(List::new(vec![
+                            atom("b"),
+                            ExprKind::List(List::new(vec![
+                                atom("unquote"),
+                                ExprKind::List(List::new(vec![atom("g")])),
+                            ])),
+                        ])),
+                    ])),
+                ])),
+            ]))],
+        )
+    }
+
     #[test]
     fn test_unquote_shorthand() {
         assert_parse(
@@ -2067,14 +2617,98 @@ mod parser_tests {
     #[test]
     fn test_unquote_splicing_shorthand() {
         assert_parse(
-            ",@(+ 1 2)",
+            "`(a ,@(+ 1 2))",
             &[ExprKind::List(List::new(vec![
-                atom("unquote-splicing"),
-                ExprKind::List(List::new(vec![atom("+"), int(1), int(2)])),
+                atom("quasiquote"),
+                ExprKind::List(List::new(vec![
+                    atom("a"),
+                    ExprKind::List(List::new(vec![
+                        atom("#%unquote-splicing"),
+                        ExprKind::List(List::new(vec![atom("+"), int(1), int(2)])),
+                    ])),
+                ])),
             ]))],
         )
     }
 
+    #[test]
+    fn test_misplaced_unquote_splicing() {
+        assert_parse_err(
+            ",@x",
+            ParseError::MisplacedSplice(Span::new(0, 2, None), None),
+        );
+        assert_parse_err(
+            "`,@x",
+            ParseError::MisplacedSplice(Span::new(1, 3, None), None),
+        );
+        assert_parse_err(
+            "(list `,@x)",
+            ParseError::MisplacedSplice(Span::new(7, 9, None), None),
+        );
+
+        // Quoted, it's only data
+        parses("',@x");
+    }
+
+    #[test]
+    fn test_dotted_lists() {
+        fn parse(s: &str) -> Result<Vec<ExprKind>> {
+            Parser::new(s, None).with_dotted_lists().collect()
+        }
+
+        assert_eq!(
+            parse("(a b . c)"),
+            Ok(vec![ExprKind::List(
+                List::new(vec![atom("a"), atom("b"), atom("c")]).make_improper()
+            )])
+        );
+
+        let [ExprKind::Quote(quote)] = &parse("'((1 . 2) 3)").unwrap()[..] else {
+            panic!("expected a quote");
+        };
+        assert_eq!(
+            quote.expr,
+            ExprKind::List(List::new(vec![
+                ExprKind::List(List::new(vec![int(1), int(2)]).make_improper()),
+                int(3),
+            ]))
+        );
+        assert_eq!(quote.expr.to_string(), "((1 . 2) 3)");
+
+        let [ExprKind::LambdaFunction(lambda)] = &parse("(lambda (x . rest) rest)").unwrap()[..]
+        else {
+            panic!("expected a lambda");
+        };
+        assert!(lambda.rest);
+        assert_eq!(lambda.args, vec![atom("x"), atom("rest")]);
+
+        for (input, dot) in [
+            ("(a . b c)", 3),
+            ("(a . )", 3),
+            ("( . a)", 2),
+            ("(a . b . c)", 3),
+        ] {
+            assert_eq!(
+                parse(input),
+                Err(ParseError::MisplacedDot(
+                    Span::new(dot, dot + 1, None),
+                    None
+                )),
+                "{input}"
+            );
+        }
+
+        // Without dotted lists, `.` is just an identifier
+        assert_parse(
+            "(a . b)",
+            &[ExprKind::List(List::new(vec![
+                atom("a"),
+                atom("."),
+                atom("b"),
+            ]))],
+        );
+    }
+
     #[test]
     fn test_unquote_splicing_normal() {
         assert_parse(
@@ -2481,4 +3115,340 @@ mod parser_tests {
 
         println!("{:#?}", a);
     }
+
+    #[test]
+    fn test_curly_infix() {
+        let parse = |s| {
+            Parser::new(s, None)
+                .with_curly_infix()
+                .collect::<Result<Vec<_>>>()
+        };
+
+        assert_eq!(
+            parse("{1 + 2}").unwrap(),
+            vec![ExprKind::List(List::new(vec![atom("+"), int(1), int(2)]))]
+        );
+
+        assert_eq!(
+            parse("(f {1 + 2 + 3} {x})").unwrap(),
+            vec![ExprKind::List(List::new(vec![
+                atom("f"),
+                ExprKind::List(List::new(vec![atom("+"), int(1), int(2), int(3)])),
+                atom("x"),
+            ]))]
+        );
+
+        assert_eq!(
+            parse("{1 + 2 * 3}").unwrap(),
+            vec![ExprKind::List(List::new(vec![
+                atom("$nfx$"),
+                int(1),
+                atom("+"),
+                int(2),
+                atom("*"),
+                int(3),
+            ]))]
+        );
+    }
+
+    #[test]
+    fn test_vector_literal() {
+        assert_parse(
+            "#(1 a)",
+            &[ExprKind::List(List::new(vec![
+                atom("vector"),
+                ExprKind::Quote(Quote::new(int(1), SyntaxObject::default(TokenType::Quote)).into()),
+                ExprKind::Quote(
+                    Quote::new(atom("a"), SyntaxObject::default(TokenType::Quote)).into(),
+                ),
+            ]))],
+        );
+    }
+
+    #[test]
+    fn test_quoted_vector_literal() {
+        let quote = |expr| {
+            ExprKind::Quote(Box::new(Quote::new(
+                expr,
+                SyntaxObject::default(TokenType::Quote),
+            )))
+        };
+
+        assert_parse(
+            "'#(1 a)",
+            &[quote(ExprKind::List(
+                List::new(vec![int(1), atom("a")]).make_vector(VectorKind::Vector),
+            ))],
+        );
+        assert_parse(
+            "'(x #(1 2))",
+            &[quote(ExprKind::List(List::new(vec![
+                atom("x"),
+                ExprKind::List(List::new(vec![int(1), int(2)]).make_vector(VectorKind::Vector)),
+            ])))],
+        );
+    }
+
+    #[test]
+    fn test_quoted_bytevector_literal() {
+        assert_parse(
+            "'#u8(1 255)",
+            &[ExprKind::Quote(Box::new(Quote::new(
+                ExprKind::List(
+                    List::new(vec![int(1), int(255)]).make_vector(VectorKind::Bytevector),
+                ),
+                SyntaxObject::default(TokenType::Quote),
+            )))],
+        );
+    }
+
+    #[test]
+    fn test_bytevector_literal() {
+        assert_parse(
+            "#u8(1 255)",
+            &[ExprKind::List(List::new(vec![
+                atom("bytevector"),
+                ExprKind::Quote(Quote::new(int(1), SyntaxObject::default(TokenType::Quote)).into()),
+                ExprKind::Quote(
+                    Quote::new(int(255), SyntaxObject::default(TokenType::Quote)).into(),
+                ),
+            ]))],
+        );
+    }
+
+    #[test]
+    fn test_strict_and_lenient_stray_close() {
+        assert_parse_err("(a))", unexpected_close(3));
+
+        let lenient: Result<Vec<ExprKind>> = Parser::new("(a)) b", None).lenient().collect();
+        assert_eq!(
+            lenient.unwrap(),
+            vec![ExprKind::List(List::new(vec![atom("a")])), atom("b")]
+        );
+    }
+
+    #[test]
+    fn test_strict_and_lenient_single_datum() {
+        let a = || ExprKind::List(List::new(vec![atom("a")]));
+
+        assert_eq!(Parser::new("(a) ; done", None).read_single().unwrap(), a());
+        assert!(matches!(
+            Parser::new("(a) b", None).read_single(),
+            Err(ParseError::SyntaxError(_, span, _)) if span == Span::new(4, 5, None)
+        ));
+        assert!(matches!(
+            Parser::new("(a))", None).read_single(),
+            Err(ParseError::UnexpectedClose(..))
+        ));
+        assert!(matches!(
+            Parser::new("; nothing", None).read_single(),
+            Err(ParseError::UnexpectedEOF(_))
+        ));
+
+        assert_eq!(
+            Parser::new("(a) b", None).lenient().read_single().unwrap(),
+            a()
+        );
+        assert_eq!(
+            Parser::new("(a)) b", None).lenient().read_single().unwrap(),
+            a()
+        );
+    }
+
+    #[test]
+    fn test_parse_all_top_level_forms() {
+        assert_eq!(
+            Parser::parse_without_lowering("(a (b c)) d ()").unwrap(),
+            vec![
+                ExprKind::List(List::new(vec![
+                    atom("a"),
+                    ExprKind::List(List::new(vec![atom("b"), atom("c")])),
+                ])),
+                atom("d"),
+                ExprKind::List(List::new(vec![])),
+            ]
+        );
+
+        assert_eq!(Parser::parse("(a (b"), Err(missing_close_paren(3)));
+        assert_eq!(Parser::parse("a)"), Err(unexpected_close(1)));
+    }
+
+    #[test]
+    fn test_missing_close_paren_suggestion() {
+        let err = Parser::parse("(define (f x)\n  (+ x 1)").unwrap_err();
+
+        assert_eq!(err, missing_close_paren(0));
+        assert_eq!(err.span(), Some(Span::new(0, 1, None)));
+        assert_eq!(err.suggestion(), Some("add ')' to close this list"));
+        assert_eq!(
+            err.to_string(),
+            "Parse: Unexpected EOF: add ')' to close this list"
+        );
+
+        let err = Parser::parse("#(1 2").unwrap_err();
+        assert_eq!(err.suggestion(), Some("add ')' to close this vector"));
+        assert_eq!(err.span(), Some(Span::new(0, 2, None)));
+
+        let err = Parser::new("{1 + 2", None)
+            .with_curly_infix()
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(
+            err.suggestion(),
+            Some("add '}' to close this curly-infix expression")
+        );
+
+        // Errors without a known fix have no suggestion
+        assert_eq!(Parser::parse("a)").unwrap_err().suggestion(), None);
+    }
+
+    #[test]
+    fn test_mismatched_close_delimiter() {
+        let parse = |s| {
+            Parser::new(s, None)
+                .with_curly_infix()
+                .collect::<Result<Vec<_>>>()
+        };
+
+        assert_eq!(
+            parse("(a b}"),
+            Err(ParseError::UnexpectedToken {
+                found: TokenType::CloseCurly,
+                span: Span::new(4, 5, None),
+                expected: vec![TokenType::CloseParen],
+                source: None,
+            })
+        );
+
+        let err = parse("{1 + (2 * 3}}").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::UnexpectedToken {
+                found: TokenType::CloseCurly,
+                span: Span::new(11, 12, None),
+                expected: vec![TokenType::CloseParen],
+                source: None,
+            }
+        );
+        assert_eq!(err.span(), Some(Span::new(11, 12, None)));
+        assert_eq!(err.to_string(), "Parse: Unexpected token: }, expected )");
+
+        assert_eq!(
+            parse("{a + b)"),
+            Err(ParseError::UnexpectedToken {
+                found: TokenType::CloseParen,
+                span: Span::new(6, 7, None),
+                expected: vec![TokenType::CloseCurly],
+                source: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let round_trip = |s: &str| {
+            let exprs = Parser::parse(s).unwrap();
+            let printed = exprs
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            assert_eq!(
+                Parser::parse(&printed).unwrap(),
+                exprs,
+                "printed as {printed}"
+            );
+            printed
+        };
+
+        assert_eq!(round_trip("(quote x)"), "'x");
+        assert_eq!(
+            round_trip("'(1 2.0 \"a\\\"b\\n\")"),
+            "'(1 2.0 \"a\\\"b\\n\")"
+        );
+        assert_eq!(
+            round_trip("\"tab\\tback\\\\slash\\x01\""),
+            "\"tab\\tback\\\\slash\\x1;\""
+        );
+        assert_eq!(round_trip("(f -3.0 0.5)"), "(f -3.0 0.5)");
+
+        round_trip("(define (f x) (if (< x 10) 'small (begin (display \"big\") x)))");
+        round_trip("(define xs #(a ''b #\\space))");
+        assert_eq!(
+            round_trip("(lambda args (apply + args))"),
+            "(lambda args (apply + args))"
+        );
+    }
+
+    #[test]
+    fn test_parse_file_forms_in_order() {
+        let source = "(define a 1)\n(define b 2)\n(display (+ a b))";
+
+        let forms = Parser::parse_without_lowering(source).unwrap();
+        assert_eq!(forms.len(), 3);
+        assert_eq!(
+            forms.iter().map(|x| x.to_string()).collect::<Vec<_>>(),
+            vec!["(define a 1)", "(define b 2)", "(display (+ a b))"]
+        );
+    }
+
+    #[test]
+    fn test_parse_error_positions() {
+        // The second form never closes, so the error points at its open paren
+        let source = "(define a 1)\n(define (f x)\n  (+ x 1)\n(f a)";
+        let err = Parser::parse(source).unwrap_err();
+
+        assert_eq!(err, missing_close_paren(13));
+        assert_eq!(
+            Lexer::new(source).line_column(err.span().unwrap().start),
+            (2, 1)
+        );
+
+        let source = "(a)\n(b))";
+        let err = Parser::parse(source).unwrap_err();
+
+        assert_eq!(err, unexpected_close(7));
+        assert_eq!(
+            Lexer::new(source).line_column(err.span().unwrap().start),
+            (2, 4)
+        );
+        assert_eq!(err.to_string(), "Parse: Unexpected closing delimiter: )");
+    }
+
+    #[test]
+    fn test_skip_datum() {
+        let mut parser = Parser::new("(a (b) c) d", None);
+
+        assert_eq!(parser.skip_datum().unwrap(), Span::new(0, 9, None));
+        assert_eq!(parser.offset(), 9);
+        assert_eq!(parser.next().unwrap().unwrap(), atom("d"));
+
+        let mut parser = Parser::new("'(a 'b) #; c d e", None);
+
+        assert_eq!(parser.skip_datum().unwrap(), Span::new(0, 7, None));
+        assert_eq!(parser.skip_datum().unwrap(), Span::new(8, 14, None));
+        assert_eq!(parser.next().unwrap().unwrap(), atom("e"));
+
+        assert_eq!(
+            Parser::new("(a", None).skip_datum(),
+            Err(ParseError::UnexpectedEOF(None))
+        );
+        assert!(matches!(
+            Parser::new(")", None).skip_datum(),
+            Err(ParseError::UnexpectedClose(..))
+        ));
+    }
+
+    #[test]
+    fn test_datum_comments() {
+        assert_parse(
+            "(a #;(b c) d) #;e f",
+            &[
+                ExprKind::List(List::new(vec![atom("a"), atom("d")])),
+                atom("f"),
+            ],
+        );
+        assert_parse("#; #; a b c", &[atom("c")]);
+        assert_parse("(a #;'b)", &[ExprKind::List(List::new(vec![atom("a")]))]);
+    }
 }
@@ -1,12 +1,16 @@
+use core::fmt;
 use core::ops::Range;
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
 // use crate::{list, rvals::FromSteelVal, rvals::IntoSteelVal};
 
 // use crate::rvals::SteelVal;
 
-use super::parser::SourceId;
+#[derive(
+    Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, Debug, Ord, PartialOrd,
+)]
+#[repr(C)]
+pub struct SourceId(pub usize);
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
 #[repr(C)]
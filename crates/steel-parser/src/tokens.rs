@@ -1,13 +1,16 @@
 use crate::lexer;
-use crate::parser::SourceId;
-use crate::span::Span;
+use crate::span::{SourceId, Span};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Display};
+use core::num::ParseIntError;
 use core::ops;
+use core::str::FromStr;
+use num::traits::float::FloatCore;
 use num::{BigInt, Rational32, Signed};
 use serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
-use std::fmt::{self, Display};
-use std::num::ParseIntError;
-use std::str::FromStr;
 use TokenType::*;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,7 +34,7 @@ impl Display for DecodeHexError {
     }
 }
 
-impl std::error::Error for DecodeHexError {}
+impl core::error::Error for DecodeHexError {}
 
 pub fn decode_hex(s: &str) -> Result<Vec<u8>, DecodeHexError> {
     if s.len() % 2 != 0 {
@@ -90,6 +93,10 @@ pub fn parse_unicode_str(slice: &str) -> Option<char> {
 pub enum TokenType<S> {
     OpenParen,
     CloseParen,
+    OpenCurly,
+    CloseCurly,
+    OpenVector,
+    OpenBytevector,
     QuoteTick,
     QuasiQuote,
     Unquote,
@@ -108,19 +115,82 @@ pub enum TokenType<S> {
     Quote,
     SyntaxRules,
     DefineSyntax,
+    /// Exactly three dots, used for repetition in `syntax-rules` patterns. Any other
+    /// number of dots, like `..` or `....`, is an ordinary identifier.
     Ellipses,
+    /// `=>`, as in `(cond (test => proc))`. Only produced by a
+    /// [`Lexer`](crate::lexer::Lexer) built [`with_arrows`](crate::lexer::Lexer::with_arrows).
+    Arrow,
     Set,
     Require,
     CharacterLiteral(char),
-    Comment,
+    Comment(CommentLevel),
+    /// `#;`, which comments out the datum following it.
+    DatumComment,
+    /// `,` as a separator, only produced by a [`Lexer`](crate::lexer::Lexer) built
+    /// [`with_comma_separators`](crate::lexer::Lexer::with_comma_separators).
+    Comma,
+    /// A run of whitespace, only produced by a [`Lexer`](crate::lexer::Lexer) built
+    /// [`with_trivia`](crate::lexer::Lexer::with_trivia).
+    Whitespace,
     BooleanLiteral(bool),
+    /// A boolean written `#t` or `#f`, only produced by a [`Lexer`](crate::lexer::Lexer)
+    /// built [`with_normalize_booleans(false)`](crate::lexer::Lexer::with_normalize_booleans).
+    /// Otherwise those are read as a [`TokenType::BooleanLiteral`] like `#true` and `#false`.
+    ShortBooleanLiteral(bool),
     Identifier(S),
     Keyword(S),
     Number(NumberLiteral),
     StringLiteral(String),
+    /// A string literal cut off by the end of the input, holding the contents read so far.
+    /// Only produced by a [`Lexer`](crate::lexer::Lexer) built
+    /// [`with_partial_strings`](crate::lexer::Lexer::with_partial_strings).
+    PartialString(String),
+    Error,
+}
+
+/// See [`TokenType::category`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TokenCategory {
+    /// Special forms like `define` or `if`, and keywords like `#:key`
+    Keyword,
+    Number,
+    String,
+    Character,
+    /// Reader prefixes like `'` or `,@`, and `...`
+    Operator,
+    Delimiter,
+    Identifier,
+    Boolean,
+    Comment,
+    Whitespace,
+    /// Input the lexer couldn't make sense of
     Error,
 }
 
+/// The kind of line comment, going by how many semicolons it starts with: by convention
+/// `;;;` comments describe a whole file, `;;` comments a section of it, and `;` comments
+/// sit next to the code they explain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommentLevel {
+    /// `;`
+    Inline,
+    /// `;;`
+    Section,
+    /// `;;;` or more
+    File,
+}
+
+impl CommentLevel {
+    pub fn from_semicolons(count: usize) -> Self {
+        match count {
+            0 | 1 => CommentLevel::Inline,
+            2 => CommentLevel::Section,
+            _ => CommentLevel::File,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NumberLiteral {
     Real(RealLiteral),
@@ -210,7 +280,14 @@ impl Display for RealLiteral {
                     write!(f, "{}", lexer::NEG_INFINITY)
                 } else if x.is_infinite() {
                     write!(f, "{}", lexer::INFINITY)
+                } else if FloatCore::abs(*x) >= 1e21 {
+                    // Rather than writing out hundreds of zeros
+                    write!(f, "{x:e}")
+                } else if FloatCore::fract(*x) == 0.0 {
+                    // Keep the decimal point so the literal reads back as a float
+                    write!(f, "{x:.1}")
                 } else {
+                    // The shortest digits that read back as the same float
                     x.fmt(f)
                 }
             }
@@ -286,6 +363,85 @@ impl From<BigInt> for IntLiteral {
     }
 }
 
+impl<S> TokenType<S> {
+    pub fn is_open_delimiter(&self) -> bool {
+        matches!(self, OpenParen | OpenCurly | OpenVector | OpenBytevector)
+    }
+
+    pub fn is_close_delimiter(&self) -> bool {
+        matches!(self, CloseParen | CloseCurly)
+    }
+
+    /// Comments and whitespace, which carry no meaning for the parser.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Comment(_) | Whitespace)
+    }
+
+    /// Reader prefixes like `'` or `,@` belong to the datum that follows them.
+    pub fn is_reader_prefix(&self) -> bool {
+        matches!(
+            self,
+            QuoteTick
+                | QuasiQuote
+                | Unquote
+                | UnquoteSplice
+                | QuoteSyntax
+                | QuasiQuoteSyntax
+                | UnquoteSyntax
+                | UnquoteSpliceSyntax
+        )
+    }
+
+    /// The broad kind of this token, for highlighting it in an editor.
+    pub fn category(&self) -> TokenCategory {
+        match self {
+            OpenParen | CloseParen | OpenCurly | CloseCurly | OpenVector | OpenBytevector
+            | Comma => TokenCategory::Delimiter,
+            QuoteTick | QuasiQuote | Unquote | UnquoteSplice | QuoteSyntax | QuasiQuoteSyntax
+            | UnquoteSyntax | UnquoteSpliceSyntax | Ellipses | Arrow => TokenCategory::Operator,
+            If | Define | Let | TestLet | Return | Begin | Lambda | Quote | SyntaxRules
+            | DefineSyntax | Set | Require | Keyword(_) => TokenCategory::Keyword,
+            CharacterLiteral(_) => TokenCategory::Character,
+            Comment(_) | DatumComment => TokenCategory::Comment,
+            Whitespace => TokenCategory::Whitespace,
+            BooleanLiteral(_) | ShortBooleanLiteral(_) => TokenCategory::Boolean,
+            Identifier(_) => TokenCategory::Identifier,
+            Number(_) => TokenCategory::Number,
+            StringLiteral(_) | PartialString(_) => TokenCategory::String,
+            Error => TokenCategory::Error,
+        }
+    }
+
+    /// The TextMate scope an editor should highlight this token with, e.g.
+    /// `keyword.control` for `if` or `constant.numeric` for `42`. This refines
+    /// [`category`](TokenType::category) for the tokens an editor tells apart within one.
+    pub fn scope(&self) -> &'static str {
+        match self {
+            OpenCurly | CloseCurly => return "punctuation.section.braces",
+            Comma => return "punctuation.separator.comma",
+            Ellipses => return "keyword.operator.ellipsis",
+            Arrow => return "keyword.operator.arrow",
+            Keyword(_) => return "constant.other.keyword",
+            DatumComment => return "comment.block",
+            _ => {}
+        }
+
+        match self.category() {
+            TokenCategory::Delimiter => "punctuation.section.parens",
+            TokenCategory::Operator => "keyword.operator.quote",
+            TokenCategory::Keyword => "keyword.control",
+            TokenCategory::Character => "constant.character",
+            TokenCategory::Comment => "comment.line",
+            TokenCategory::Whitespace => "text.whitespace",
+            TokenCategory::Boolean => "constant.language.boolean",
+            TokenCategory::Identifier => "variable.other",
+            TokenCategory::Number => "constant.numeric",
+            TokenCategory::String => "string.quoted.double",
+            TokenCategory::Error => "invalid.illegal",
+        }
+    }
+}
+
 impl<'a> TokenType<&'a str> {
     pub fn to_owned<T: From<&'a str>>(self) -> TokenType<T> {
         match self {
@@ -293,16 +449,25 @@ impl<'a> TokenType<&'a str> {
             TokenType::Keyword(i) => TokenType::Keyword(i.into()),
             OpenParen => OpenParen,
             CloseParen => CloseParen,
+            OpenCurly => OpenCurly,
+            CloseCurly => CloseCurly,
+            OpenVector => OpenVector,
+            OpenBytevector => OpenBytevector,
             CharacterLiteral(x) => CharacterLiteral(x),
             BooleanLiteral(x) => BooleanLiteral(x),
+            ShortBooleanLiteral(x) => ShortBooleanLiteral(x),
             Number(x) => Number(x),
             StringLiteral(x) => StringLiteral(x),
+            PartialString(x) => PartialString(x),
             QuoteTick => QuoteTick,
             Unquote => Unquote,
             QuasiQuote => QuasiQuote,
             UnquoteSplice => UnquoteSplice,
             Error => Error,
-            Comment => Comment,
+            Comment(level) => Comment(level),
+            DatumComment => DatumComment,
+            Comma => Comma,
+            Whitespace => Whitespace,
             If => If,
             Define => Define,
             Let => Let,
@@ -314,6 +479,7 @@ impl<'a> TokenType<&'a str> {
             DefineSyntax => DefineSyntax,
             SyntaxRules => SyntaxRules,
             Ellipses => Ellipses,
+            Arrow => Arrow,
             Set => Set,
             Require => Require,
             QuasiQuoteSyntax => QuasiQuoteSyntax,
@@ -329,16 +495,25 @@ impl<'a> TokenType<&'a str> {
             TokenType::Keyword(i) => TokenType::Keyword(func(i)),
             OpenParen => OpenParen,
             CloseParen => CloseParen,
+            OpenCurly => OpenCurly,
+            CloseCurly => CloseCurly,
+            OpenVector => OpenVector,
+            OpenBytevector => OpenBytevector,
             CharacterLiteral(x) => CharacterLiteral(x),
             BooleanLiteral(x) => BooleanLiteral(x),
+            ShortBooleanLiteral(x) => ShortBooleanLiteral(x),
             Number(x) => Number(x),
             StringLiteral(x) => StringLiteral(x),
+            PartialString(x) => PartialString(x),
             QuoteTick => QuoteTick,
             Unquote => Unquote,
             QuasiQuote => QuasiQuote,
             UnquoteSplice => UnquoteSplice,
             Error => Error,
-            Comment => Comment,
+            Comment(level) => Comment(level),
+            DatumComment => DatumComment,
+            Comma => Comma,
+            Whitespace => Whitespace,
             If => If,
             Define => Define,
             Let => Let,
@@ -350,6 +525,7 @@ impl<'a> TokenType<&'a str> {
             DefineSyntax => DefineSyntax,
             SyntaxRules => SyntaxRules,
             Ellipses => Ellipses,
+            Arrow => Arrow,
             Set => Set,
             Require => Require,
             QuasiQuoteSyntax => QuasiQuoteSyntax,
@@ -376,16 +552,49 @@ fn character_special_display(c: char, f: &mut fmt::Formatter) -> fmt::Result {
     }
 }
 
+// Writes `s` back out as a string literal, escaping whatever the lexer would otherwise
+// read differently.
+fn string_literal_display(s: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "\"")?;
+    string_contents_display(s, f)?;
+    write!(f, "\"")
+}
+
+fn string_contents_display(s: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\t' => write!(f, "\\t")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\0' => write!(f, "\\0")?,
+            c if c.is_ascii_control() => write!(f, "\\x{:x};", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    Ok(())
+}
+
 impl<T: Display> fmt::Display for TokenType<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             OpenParen => write!(f, "("),
-            CloseParen => write!(f, "("),
+            CloseParen => write!(f, ")"),
+            OpenCurly => write!(f, "{{"),
+            CloseCurly => write!(f, "}}"),
+            OpenVector => write!(f, "#("),
+            OpenBytevector => write!(f, "#u8("),
             CharacterLiteral(x) => character_special_display(*x, f),
             BooleanLiteral(x) => write!(f, "#{x}"),
+            ShortBooleanLiteral(x) => write!(f, "#{}", if *x { 't' } else { 'f' }),
             Identifier(x) => write!(f, "{x}"),
             Number(x) => write!(f, "{x}"),
-            StringLiteral(x) => write!(f, "\"{x}\""),
+            StringLiteral(x) => string_literal_display(x, f),
+            PartialString(x) => {
+                write!(f, "\"")?;
+                string_contents_display(x, f)
+            }
             Keyword(x) => write!(f, "{x}"),
             QuoteTick => write!(f, "'"),
             Unquote => write!(f, ","),
@@ -396,7 +605,10 @@ impl<T: Display> fmt::Display for TokenType<T> {
             UnquoteSyntax => write!(f, "#,"),
             UnquoteSpliceSyntax => write!(f, "#,@"),
             Error => write!(f, "error"),
-            Comment => write!(f, ""),
+            Comment(_) => write!(f, ""),
+            DatumComment => write!(f, "#;"),
+            Comma => write!(f, ","),
+            Whitespace => write!(f, " "),
             If => write!(f, "if"),
             Define => write!(f, "define"),
             Let => write!(f, "let"),
@@ -408,6 +620,7 @@ impl<T: Display> fmt::Display for TokenType<T> {
             DefineSyntax => write!(f, "define-syntax"),
             SyntaxRules => write!(f, "syntax-rules"),
             Ellipses => write!(f, "..."),
+            Arrow => write!(f, "=>"),
             Set => write!(f, "set!"),
             Require => write!(f, "require"),
         }
@@ -439,6 +652,11 @@ impl<'a, T> Token<'a, T> {
         &self.ty
     }
 
+    /// See [`TokenType::category`].
+    pub fn category(&self) -> TokenCategory {
+        self.ty.category()
+    }
+
     pub const fn span(&self) -> Span {
         self.span
     }